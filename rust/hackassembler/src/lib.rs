@@ -1,42 +1,23 @@
-// converting hack assembly lang to hack machine lang
+// hack assembly lang to hack machine lang: shared by hackassembler and the jack build driver
 // コンピュータシステムの理論と実装 §6
 
-use std::env;
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader};
 use std::collections::HashMap;
 
-fn main() {
-    // get path from command line
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        panic!("input filename: ./hackassembler path/to/foo.asm");
-    }
-    let path = PathBuf::from(&args[1]);
-
-    // open foo.asm file
-    let asmfile = match File::open(&path) {
-        Err(why) => panic!("couldn't open {}: {}", path.display(), why),
-        Ok(file) => file,
-    };
-
-    // convert foo.asm to binary
-    let asm_mid_code = MidAsmCode::asm_to_midcode(&asmfile);
+// foo.asm -> (binary code, static variables' addresses, source map if asm code has vm debug info)
+pub fn assemble(asmfile: &File) -> (String, String, Option<String>) {
+    let asm_mid_code = MidAsmCode::asm_to_midcode(asmfile);
     let binary_mid_code = MidAsmCode::midcode_to_binary(asm_mid_code);
-    let binary_str = MidAsmCode::binary_to_Str(binary_mid_code);
-
-    // write binary data to file
-    let mut new_path = path.clone();
-    new_path.set_extension("hack");
-    let mut hackfile = match File::create(&new_path) {
-        Err(why) => panic!("couldn't create {}: {}", new_path.display(), why),
-        Ok(file) => file,
-    };
-    writeln!(hackfile, "{}", binary_str);
+    let static_map_str = MidAsmCode::static_map_to_string(&binary_mid_code);
+    let source_map_str = MidAsmCode::source_map_to_string(&binary_mid_code);
+    let binary_str = MidAsmCode::binary_to_str(binary_mid_code);
+    return (binary_str, static_map_str, source_map_str);
 }
 
-
 struct Row {
     new_row_num: usize,
     row_num: usize,
@@ -52,11 +33,13 @@ impl Row {
 struct MidAsmCode {
     row: Vec<Row>,
     var: HashMap<String, usize>,
+    new_var: Vec<String>,       // variables allocated from RAM[16] in order
+    source_map: Vec<(usize, String, usize)>,    // (rom address, vm file, vm line) from [// @vm Foo.vm line]
 }
 
 impl MidAsmCode {
     fn new() -> Self {
-        MidAsmCode {row: vec![], var: HashMap::new()}
+        MidAsmCode {row: vec![], var: HashMap::new(), new_var: vec![], source_map: vec![]}
     }
 
     fn asm_to_midcode(file: &File) -> MidAsmCode {
@@ -106,7 +89,19 @@ impl MidAsmCode {
                 } else if line_vec[0].chars().nth(0).unwrap() == '/' && line_vec[0].chars().nth(1).unwrap() == '/' {
                     // skip comments
                     comment_error_handle = false;
-                } else if line_vec[0].chars().nth(0).unwrap() == '(' && line_vec[0].chars().last().unwrap() == ')' {
+
+                    // debug info from vm translator: [// @vm Foo.vm line] is related to the next A or C type code
+                    if words_num >= 4 && line_vec[0] == "//" && line_vec[1] == "@vm" {
+                        if let Ok(vm_line) = line_vec[3].parse::<usize>() {
+                            if let Some(last) = midcode.source_map.last() {
+                                if last.0 == new_row_num {
+                                    midcode.source_map.pop();
+                                }
+                            }
+                            midcode.source_map.push((new_row_num, line_vec[2].to_string(), vm_line));
+                        }
+                    }
+                } else if line_vec[0].chars().nth(0).unwrap() == '(' && line_vec[0].ends_with(')') {
                     // register label variables
                     if line_length == 2 {
                         panic!("syntax error: invalid label: in line {}", row_num);
@@ -122,12 +117,8 @@ impl MidAsmCode {
                 }
             }
 
-            if words_num >= 2 {
-                if !(line_vec[1].chars().nth(0).unwrap() == '/' && line_vec[1].chars().nth(1).unwrap() == '/') {
-                    if comment_error_handle {
-                        eprintln!("warning: some codes after blank will be ignored: in line {}", row_num);
-                    }
-                }
+            if words_num >= 2 && !line_vec[1].starts_with("//") && comment_error_handle {
+                eprintln!("warning: some codes after blank will be ignored: in line {}", row_num);
             }
         }
         return midcode;
@@ -136,13 +127,14 @@ impl MidAsmCode {
     fn midcode_to_binary(midcode: MidAsmCode) -> MidAsmCode {
         let mut binary = MidAsmCode::new();
         binary.var = midcode.var;
+        binary.source_map = midcode.source_map;
         let mut new_variable_counter = 16;
 
         for line in midcode.row {
             if let Some('@') = line.code.chars().nth(0) {
                 // A-type code: relating value and variables
                 let variable: String = line.code.chars().skip(1).collect();
-                let mut value = 0;
+                let value;
                 if let Ok(val) = variable.parse::<usize>() {
                     // when input string is number(usize)
                     value = val;
@@ -153,6 +145,7 @@ impl MidAsmCode {
                     } else {
                         // when variable is not registered
                         value = new_variable_counter;
+                        binary.new_var.push(variable.clone());
                         binary.var.insert(variable, value);
                         new_variable_counter += 1;
                     }
@@ -169,9 +162,9 @@ impl MidAsmCode {
                 // C-type code
                 // dividing C-type code to dest/comp/jump order
                 let mut dest_code = "".to_string();
-                let mut comp_code = "".to_string();
+                let comp_code;
                 let mut jump_code = "".to_string();
-                let mut temp = "".to_string();
+                let temp;
                 if let Some(position_eq) = line.code.chars().position(|c| c == '=') {
                     if position_eq == 0 || position_eq == line.code.chars().count() - 1 {
                         panic!("syntax error: invalid C-type order: in line {}", line.row_num);
@@ -202,7 +195,32 @@ impl MidAsmCode {
         return binary;
     }
 
-    fn binary_to_Str(midcode: MidAsmCode) -> String {
+    // static variables of vm code are named as Foo.x: "file index address" per line
+    fn static_map_to_string(midcode: &MidAsmCode) -> String {
+        let mut string = "// file index address\n".to_string();
+        for variable in &midcode.new_var {
+            if let Some((file, index)) = variable.rsplit_once('.') {
+                if !file.is_empty() && index.parse::<usize>().is_ok() {
+                    string += &format!("{} {} {}\n", file, index, midcode.var[variable]);
+                }
+            }
+        }
+        return string;
+    }
+
+    // "rom_address file line" per line: the code from rom_address up to the next entry belongs to the vm line
+    fn source_map_to_string(midcode: &MidAsmCode) -> Option<String> {
+        if midcode.source_map.is_empty() {
+            return None;
+        }
+        let mut string = "// rom_address file line\n".to_string();
+        for (rom_address, file, line) in &midcode.source_map {
+            string += &format!("{} {} {}\n", rom_address, file, line);
+        }
+        return Some(string);
+    }
+
+    fn binary_to_str(midcode: MidAsmCode) -> String {
         let mut string: String = "".to_string();
         for line in midcode.row {
             string = format!("{}{}\n", string, line.code);
@@ -242,7 +260,7 @@ fn c_order_to_binary(comp: &String, dest: &String, jump: &String, row_number: us
                       else if comp == "D|M" {"1010101"}
                       else {panic!("syntax error: invalid C-type order: in line {}", row_number)};
 
-    let dest_binary = if dest == "" {"000"}
+    let dest_binary = if dest.is_empty() {"000"}
                       else if dest == "M" {"001"}
                       else if dest == "D" {"010"}
                       else if dest == "DM" || dest == "MD" {"011"}
@@ -252,7 +270,7 @@ fn c_order_to_binary(comp: &String, dest: &String, jump: &String, row_number: us
                       else if dest == "ADM" {"111"}
                       else {panic!("syntax error: invalid C-type order: in line {}", row_number)};
     
-    let jump_binary = if jump == "" {"000"}
+    let jump_binary = if jump.is_empty() {"000"}
                       else if jump == "JGT" {"001"}
                       else if jump == "JEQ" {"010"}
                       else if jump == "JGE" {"011"}
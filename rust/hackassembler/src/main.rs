@@ -0,0 +1,59 @@
+// converting hack assembly lang to hack machine lang
+// コンピュータシステムの理論と実装 §6
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::fs::File;
+use std::path::PathBuf;
+
+use hackassembler::assemble;
+use tool_output::{Marker, OutDir};
+
+fn main() {
+    // get path and options from command line
+    //   -o dir, --out-dir dir: writes foo.hack (and foo.statics, foo.srcmap) into dir instead of next to foo.asm
+    //   -o -: writes only foo.hack into stdout
+    let args: Vec<String> = env::args().collect();
+    let mut out_dir = OutDir::NextToInput;
+    let mut input_path = None;
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        if arg == "-o" || arg == "--out-dir" {
+            match args_iter.next() {
+                Some(dir) => out_dir = OutDir::from_arg(dir),
+                None => panic!("error: {} needs an output directory (or - for stdout)", arg),
+            }
+        } else {
+            input_path = Some(PathBuf::from(arg));
+        }
+    }
+    let path = match input_path {
+        Some(path) => path,
+        None => panic!("input filename: ./hackassembler [-o out_dir|-] path/to/foo.asm"),
+    };
+
+    // open foo.asm file
+    let asmfile = match File::open(&path) {
+        Err(why) => panic!("couldn't open {}: {}", path.display(), why),
+        Ok(file) => file,
+    };
+
+    // convert foo.asm to binary
+    let (binary_str, static_map_str, source_map_str) = assemble(&asmfile);
+
+    // write binary data to file
+    out_dir.write(&out_dir.output_path(&path, "hack"), &format!("{}\n", binary_str), "hackassembler", Marker::NoComment);
+    if let OutDir::Stdout = out_dir {
+        return;
+    }
+
+    // write static variables' addresses for debugger: foo.statics
+    out_dir.write(&out_dir.output_path(&path, "statics"), &static_map_str, "hackassembler", Marker::Comment("//"));
+
+    // write source map when asm code has vm debug info: foo.srcmap
+    if let Some(source_map_str) = source_map_str {
+        out_dir.write(&out_dir.output_path(&path, "srcmap"), &source_map_str, "hackassembler", Marker::Comment("//"));
+    }
+}
@@ -0,0 +1,156 @@
+// abstract syntax tree of jack lang
+// every node keeps the span of its first token for diagnostics
+// コンピュータシステムの理論と実装 §10, §11
+
+use crate::lexical_analysis::{Keyword, Span};
+
+// class :: "class" className "{" classVarDec* subroutineDec* "}"
+pub struct Class {
+    pub name: String,
+    pub var_decs: Vec<ClassVarDec>,
+    pub subroutines: Vec<SubroutineDec>,
+    pub span: Span,
+    pub doc: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClassVarKind {
+    Static,
+    Field,
+}
+
+impl ClassVarKind {
+    // category of the symbol table
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClassVarKind::Static => "static",
+            ClassVarKind::Field => "field",
+        }
+    }
+}
+
+// classVarDec :: ("static" | "field") type varName ("," varName)* ";"
+pub struct ClassVarDec {
+    pub kind: ClassVarKind,
+    pub var_type: String,
+    pub names: Vec<(String, Span)>,
+    pub span: Span,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SubroutineKind {
+    Constructor,
+    Function,
+    Method,
+}
+
+impl SubroutineKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SubroutineKind::Constructor => "constructor",
+            SubroutineKind::Function => "function",
+            SubroutineKind::Method => "method",
+        }
+    }
+}
+
+// subroutineDec :: ("constructor" | "function" | "method") ("void" | type) subroutineName "(" parameterList ")" subroutineBody
+pub struct SubroutineDec {
+    pub kind: SubroutineKind,
+    pub return_type: String,
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub var_decs: Vec<VarDec>,
+    pub statements: Vec<Statement>,
+    pub span: Span,
+    pub doc: Option<String>,
+}
+
+pub struct Parameter {
+    pub var_type: String,
+    pub name: String,
+    pub span: Span,
+}
+
+// varDec :: "var" type varName ("," varName)* ";"
+pub struct VarDec {
+    pub var_type: String,
+    pub names: Vec<(String, Span)>,
+    pub span: Span,
+}
+
+pub enum Statement {
+    // "let" varName ("[" expression "]")? "=" expression ";"
+    Let {name: String, index: Option<Expression>, value: Expression, span: Span},
+    // "if" "(" expression ")" "{" statements "}" ("else" "{" statements "}")?
+    // else if (--extensions) is an if statement in else_statements
+    If {condition: Expression, then_statements: Vec<Statement>, else_statements: Option<Vec<Statement>>, span: Span},
+    // "while" "(" expression ")" "{" statements "}"
+    While {condition: Expression, statements: Vec<Statement>, span: Span},
+    // "do" subroutineCall ";"
+    Do {call: SubroutineCall, span: Span},
+    // "return" expression? ";"
+    Return {value: Option<Expression>, span: Span},
+    // --extensions: "for" "(" (let | do)? ";" expression? ";" (let | do)? ")" "{" statements "}" (without condition: forever)
+    For {init: Option<Box<Statement>>, condition: Option<Expression>, update: Option<Box<Statement>>, statements: Vec<Statement>, span: Span},
+    // --extensions: "break" ";" | "continue" ";" in while or for
+    Break {span: Span},
+    Continue {span: Span},
+}
+
+impl Statement {
+    pub fn span(&self) -> &Span {
+        match self {
+            Statement::Let {span, ..} => span,
+            Statement::If {span, ..} => span,
+            Statement::While {span, ..} => span,
+            Statement::Do {span, ..} => span,
+            Statement::Return {span, ..} => span,
+            Statement::For {span, ..} => span,
+            Statement::Break {span} => span,
+            Statement::Continue {span} => span,
+        }
+    }
+}
+
+// expression :: term (op term)*
+pub struct Expression {
+    pub term: Term,
+    pub operations: Vec<(char, Term)>,
+    pub span: Span,
+}
+
+// term :: integerConstant | stringConstant | keywordConstant | varName | varName "[" expression "]" | "(" expression ")" | unaryOp term | subroutineCall
+pub enum Term {
+    IntConst(u16, Span),
+    StringConst(String, Span),
+    KeywordConst(Keyword, Span),
+    Var(String, Span),
+    ArrayElement(String, Box<Expression>, Span),
+    Parenthesized(Box<Expression>, Span),
+    Unary(char, Box<Term>, Span),
+    Call(SubroutineCall),
+}
+
+impl Term {
+    pub fn span(&self) -> &Span {
+        match self {
+            Term::IntConst(_, span) => span,
+            Term::StringConst(_, span) => span,
+            Term::KeywordConst(_, span) => span,
+            Term::Var(_, span) => span,
+            Term::ArrayElement(_, _, span) => span,
+            Term::Parenthesized(_, span) => span,
+            Term::Unary(_, _, span) => span,
+            Term::Call(call) => &call.span,
+        }
+    }
+}
+
+// subroutineCall :: ((className | varName) ".")? subroutineName "(" expressionList ")"
+pub struct SubroutineCall {
+    pub receiver: Option<String>,
+    pub name: String,
+    pub arguments: Vec<Expression>,
+    pub span: Span,
+}
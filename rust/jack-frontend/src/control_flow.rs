@@ -0,0 +1,195 @@
+// control flow analysis of subroutines
+// every path must end with 'return' (the vm code would fall through into the next function),
+// statements after 'return' (or 'break', 'continue') are unreachable, and a non-void subroutine should return a value
+
+use crate::ast::{Class, Expression, Statement, SubroutineDec, SubroutineKind, Term};
+use crate::diagnostic::Diagnostic;
+use crate::lexical_analysis::Keyword;
+
+pub fn check_control_flow(class: &Class) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for subroutine in &class.subroutines {
+        if !check_statements(&subroutine.statements, Some(subroutine), &mut diagnostics) {
+            let message = format!("{} '{}' may end without 'return'", subroutine.kind.name(), subroutine.name);
+            diagnostics.push(Diagnostic::error(&subroutine.span, message).with_hint("add a return statement at the end of every path"));
+        }
+    }
+    return diagnostics;
+}
+
+// the statements never complete normally (every path returns, halts, breaks or continues):
+// for the other analyses of the flow, e.g. definite assignment
+pub fn terminates(statements: &[Statement]) -> bool {
+    return check_statements(statements, None, &mut vec![]);
+}
+
+// returns true if the statements never complete normally (every path returns or halts)
+// subroutine: None when only the result is needed
+fn check_statements(statements: &[Statement], subroutine: Option<&SubroutineDec>, diagnostics: &mut Vec<Diagnostic>) -> bool {
+    let mut terminated = false;
+    for (i, statement) in statements.iter().enumerate() {
+        if terminated {
+            // reported once for each block, but a bare 'return;' at the end is kept by convention,
+            // e.g. after 'do Sys.halt();' or 'while (true) {...}'
+            let is_trailing_return = i == statements.len() - 1 && matches!(statement, Statement::Return {value: None, ..});
+            if !is_trailing_return {
+                diagnostics.push(Diagnostic::warning(statement.span(), "unreachable statement".to_string()));
+            }
+            break;
+        }
+        terminated = check_statement(statement, subroutine, diagnostics);
+    }
+    return terminated;
+}
+
+fn check_statement(statement: &Statement, subroutine: Option<&SubroutineDec>, diagnostics: &mut Vec<Diagnostic>) -> bool {
+    match statement {
+        Statement::Let {..} => return false,
+        Statement::If {then_statements, else_statements, ..} => {
+            let then_terminated = check_statements(then_statements, subroutine, diagnostics);
+            match else_statements {
+                Some(else_statements) => return check_statements(else_statements, subroutine, diagnostics) && then_terminated,
+                None => return false,
+            }
+        }
+        Statement::While {condition, statements, ..} => {
+            check_statements(statements, subroutine, diagnostics);
+            // while (true) never ends without break
+            return is_true(condition) && !contains_break(statements);
+        }
+        Statement::For {condition, statements, ..} => {
+            check_statements(statements, subroutine, diagnostics);
+            // for (...; ; ...) never ends without break
+            return condition.as_ref().is_none_or(is_true) && !contains_break(statements);
+        }
+        // jumps to the end or the beginning of the loop
+        Statement::Break {..} | Statement::Continue {..} => return true,
+        Statement::Do {call, ..} => {
+            // Sys.halt and Sys.error never return
+            return call.receiver.as_deref() == Some("Sys") && (call.name == "halt" || call.name == "error");
+        }
+        Statement::Return {value, span} => {
+            match subroutine {
+                Some(subroutine) if value.is_none() && subroutine.return_type != "void" => {
+                    let message = format!("{} '{}' of type {} returns no value", subroutine.kind.name(), subroutine.name, subroutine.return_type);
+                    let hint = if subroutine.kind == SubroutineKind::Constructor {"return this"} else {"the caller gets 0"};
+                    diagnostics.push(Diagnostic::warning(span, message).with_hint(hint));
+                }
+                _ => {}
+            }
+            return true;
+        }
+    }
+}
+
+// break of this loop (not of the nested loops)
+fn contains_break(statements: &[Statement]) -> bool {
+    return statements.iter().any(|statement| match statement {
+        Statement::Break {..} => true,
+        Statement::If {then_statements, else_statements, ..} => contains_break(then_statements) || else_statements.as_ref().is_some_and(|else_statements| contains_break(else_statements)),
+        _ => false,
+    });
+}
+
+fn is_true(condition: &Expression) -> bool {
+    if !condition.operations.is_empty() {
+        return false;
+    }
+    match &condition.term {
+        Term::KeywordConst(keyword, _) => return *keyword == Keyword::True,
+        Term::Parenthesized(expression, _) => return is_true(expression),
+        _ => return false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexical_analysis::Lexicon;
+    use crate::parser::parse_class;
+
+    // diagnostics of the control flow analysis of "class Main { body }"
+    fn check(body: &str) -> Vec<String> {
+        let source = format!("class Main {{\n{}\n}}\n", body);
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", &source, true);
+        let (class, syntax_errors) = parse_class(&lexicons, "Main.jack", true, false);
+        assert!(lexical_errors.is_empty() && syntax_errors.is_empty(), "invalid test code");
+        return check_control_flow(&class).iter().map(|diagnostic| format!("{} {}", diagnostic.span.line, diagnostic.message)).collect();
+    }
+
+    #[test]
+    fn if_else_returning_on_both_paths() {
+        let messages = check("function int f(int x) {
+            if (x > 0) { return 1; } else { return 2; }
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn if_without_else_falls_through() {
+        let messages = check("function int f(int x) {
+            if (x > 0) { return 1; }
+        }");
+        assert_eq!(messages, vec!["2 function 'f' may end without 'return'"]);
+    }
+
+    #[test]
+    fn while_true_never_ends() {
+        let messages = check("function void f() {
+            while (true) { do Output.printInt(1); }
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn while_true_with_nested_break_ends() {
+        let messages = check("function void f(int x) {
+            while (true) {
+                if (x > 0) { break; }
+            }
+        }");
+        assert_eq!(messages, vec!["2 function 'f' may end without 'return'"]);
+    }
+
+    #[test]
+    fn break_of_inner_loop_does_not_end_outer_loop() {
+        let messages = check("function void f() {
+            while (true) {
+                while (true) { break; }
+            }
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn statement_after_return_is_unreachable() {
+        let messages = check("function int f() {
+            return 1;
+            do Output.printInt(1);
+            do Output.printInt(2);
+        }");
+        assert_eq!(messages, vec!["4 unreachable statement"]);
+    }
+
+    #[test]
+    fn trailing_return_after_halt_or_endless_loop_is_not_unreachable() {
+        let messages = check("function void f() {
+            do Sys.halt();
+            return;
+        }
+        function void g() {
+            while (true) { }
+            return;
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn trailing_return_with_a_value_is_unreachable() {
+        let messages = check("function int f() {
+            do Sys.halt();
+            return 0;
+        }");
+        assert_eq!(messages, vec!["4 unreachable statement"]);
+    }
+}
@@ -0,0 +1,65 @@
+// diagnostics of jack code: errors and warnings with the span of the offending token and a hint
+// printed with the source line and a marker under the token
+
+use crate::lexical_analysis::Span;
+
+#[derive(PartialEq)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+pub struct Diagnostic {
+    pub level: Level,
+    pub span: Span,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(span: &Span, message: String) -> Self {
+        Diagnostic {level: Level::Error, span: span.clone(), message, hint: None}
+    }
+
+    pub fn warning(span: &Span, message: String) -> Self {
+        Diagnostic {level: Level::Warning, span: span.clone(), message, hint: None}
+    }
+
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        return self;
+    }
+
+    pub fn is_error(&self) -> bool {
+        return self.level == Level::Error;
+    }
+
+    // source: the whole jack code of span.file
+    pub fn print(&self, source: &str) {
+        let level = if self.is_error() {"error"} else {"warning"};
+        eprintln!("{}: {}: in {} line {} column {}", level, self.message, self.span.file, self.span.line, self.span.column);
+        if let Some(text) = source.lines().nth(self.span.line - 1) {
+            let line_number = self.span.line.to_string();
+            let margin = " ".repeat(line_number.len());
+            let text = text.trim_end_matches('\r');
+            // the marker covers the token, but not beyond the end of the line
+            let rest = text.chars().count().saturating_sub(self.span.column - 1);
+            let width = source.get(self.span.start..self.span.end).map_or(1, |token| token.chars().count());
+            eprintln!(" {} | {}", line_number, text);
+            let indent: String = text.chars().take(self.span.column - 1).map(|c| if c == '\t' {'\t'} else {' '}).collect();
+            eprintln!(" {} | {}{}", margin, indent, "^".repeat(width.min(rest).max(1)));
+        }
+        if let Some(hint) = &self.hint {
+            eprintln!("    hint: {}", hint);
+        }
+    }
+}
+
+// prints all diagnostics (sorted by position) and returns true if there is no error
+pub fn report(diagnostics: &mut [Diagnostic], source: &str) -> bool {
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    for diagnostic in diagnostics.iter() {
+        diagnostic.print(source);
+    }
+    return !diagnostics.iter().any(|diagnostic| diagnostic.is_error());
+}
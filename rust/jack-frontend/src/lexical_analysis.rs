@@ -0,0 +1,541 @@
+// Lexical analysis package
+// コンピュータシステムの理論と実装 §10, §11
+
+use std::fs;
+use std::path::Path;
+use crate::diagnostic::Diagnostic;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Keyword {
+    Class, Constructor, Function, Method, Field, Static, Var,
+    Int, Char, Boolean, Void, True, False, Null, This,
+    Let, Do, If, Else, While, Return,
+    // statements added by --extensions: identifiers in standard Jack
+    For, Break, Continue,
+}
+
+impl Keyword {
+    const ALL: [Keyword; 24] = [
+        Keyword::Class, Keyword::Constructor, Keyword::Function, Keyword::Method, Keyword::Field, Keyword::Static, Keyword::Var,
+        Keyword::Int, Keyword::Char, Keyword::Boolean, Keyword::Void, Keyword::True, Keyword::False, Keyword::Null, Keyword::This,
+        Keyword::Let, Keyword::Do, Keyword::If, Keyword::Else, Keyword::While, Keyword::Return,
+        Keyword::For, Keyword::Break, Keyword::Continue,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Keyword::Class => "class",
+            Keyword::Constructor => "constructor",
+            Keyword::Function => "function",
+            Keyword::Method => "method",
+            Keyword::Field => "field",
+            Keyword::Static => "static",
+            Keyword::Var => "var",
+            Keyword::Int => "int",
+            Keyword::Char => "char",
+            Keyword::Boolean => "boolean",
+            Keyword::Void => "void",
+            Keyword::True => "true",
+            Keyword::False => "false",
+            Keyword::Null => "null",
+            Keyword::This => "this",
+            Keyword::Let => "let",
+            Keyword::Do => "do",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::While => "while",
+            Keyword::Return => "return",
+            Keyword::For => "for",
+            Keyword::Break => "break",
+            Keyword::Continue => "continue",
+        }
+    }
+
+    // the keyword written as word (for, break and continue only with extensions)
+    pub fn from_word(word: &str, extensions: bool) -> Option<Keyword> {
+        let keyword = Keyword::ALL.iter().find(|keyword| keyword.name() == word).copied()?;
+        if !extensions && matches!(keyword, Keyword::For | Keyword::Break | Keyword::Continue) {
+            return None;
+        } else {
+            return Some(keyword);
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Token {
+    Keyword(Keyword),
+    Symbol(char),
+    IntConst(u16),
+    StringConst(String),
+    Identifier(String),
+}
+
+impl Token {
+    // the token as written in jack code (string constants without quotes)
+    pub fn text(&self) -> String {
+        match self {
+            Token::Keyword(keyword) => keyword.name().to_string(),
+            Token::Symbol(symbol) => symbol.to_string(),
+            Token::IntConst(integer) => integer.to_string(),
+            Token::StringConst(string) => string.to_string(),
+            Token::Identifier(name) => name.to_string(),
+        }
+    }
+
+    // the token as an element of the xml of §10
+    pub fn to_xml(&self) -> String {
+        match self {
+            Token::Keyword(keyword) => format!("<keyword> {} </keyword>", keyword.name()),
+            Token::Symbol('<') => "<symbol> &lt; </symbol>".to_string(),
+            Token::Symbol('>') => "<symbol> &gt; </symbol>".to_string(),
+            Token::Symbol('&') => "<symbol> &amp; </symbol>".to_string(),
+            Token::Symbol(symbol) => format!("<symbol> {} </symbol>", symbol),
+            Token::IntConst(integer) => format!("<integerConstant> {} </integerConstant>", integer),
+            Token::StringConst(string) => {
+                let escaped = string.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+                format!("<stringConstant> {} </stringConstant>", escaped.replace('\n', "\\n").replace('\t', "\\t"))
+            }
+            Token::Identifier(name) => format!("<identifier> {} </identifier>", name),
+        }
+    }
+
+    pub fn is_keyword(&self, keyword: Keyword) -> bool {
+        return *self == Token::Keyword(keyword);
+    }
+
+    pub fn is_symbol(&self, symbol: char) -> bool {
+        return *self == Token::Symbol(symbol);
+    }
+
+    pub fn is_identifier(&self) -> bool {
+        if let Token::Identifier(_) = self {
+            return true;
+        } else {
+            return false;
+        }
+    }
+}
+
+// the largest integer constant: `push constant` takes 15 bits
+pub const MAX_INT_CONST: u16 = 32767;
+
+// Hack character set: printable ASCII and newline (128); tab has no code, so it is printed as a space
+pub fn char_code(c: char) -> Option<u16> {
+    if (' '..='~').contains(&c) {
+        return Some(c as u16);
+    } else if c == '\n' {
+        return Some(128);
+    } else if c == '\t' {
+        return Some(32);
+    } else {
+        return None;
+    }
+}
+
+// location of a token: 1-based line and column, and the byte range [start, end) in the file
+#[derive(Clone)]
+pub struct Span {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct Lexicon {
+    pub token: Token,
+    pub span: Span,
+    // doc comment /** */ just before "class", "constructor", "function" or "method"
+    pub doc: Option<String>,
+}
+
+// jack code of a file and the byte index where each line begins
+struct Source<'a> {
+    filename: &'a str,
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Source<'a> {
+    fn new(filename: &'a str, text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        Source {filename, text, line_starts}
+    }
+
+    // span of the bytes [start, end) of the file
+    fn span(&self, start: usize, end: usize) -> Span {
+        let row_number = match self.line_starts.binary_search(&start) {
+            Ok(row_number) => row_number,
+            Err(row_number) => row_number - 1,
+        };
+        Span {
+            file: self.filename.to_string(),
+            line: row_number + 1,
+            column: self.text[self.line_starts[row_number]..start].chars().count() + 1,
+            start,
+            end,
+        }
+    }
+}
+
+impl Lexicon {
+    fn new(token: Token, span: Span) -> Self {
+        Lexicon {token, span, doc: None}
+    }
+
+    // extensions: character literals 'A' and hex literals 0x1F (integer constants), keywords for, break and continue
+    // returns the tokens and all lexical errors of the file
+    pub fn lexical_analysis(path: &Path, extensions: bool) -> (Vec<Lexicon>, Vec<Diagnostic>) {
+        let filename = path.file_name().expect("error: invalid filename").to_str().expect("error: invalid filename");
+        let contents = match fs::read_to_string(path) {
+            Err(why) => panic!("couldn't read {}: {}", path.display(), why),
+            Ok(contents) => contents,
+        };
+        return Self::tokenize(filename, &contents, extensions);
+    }
+
+    // contents: the jack code of the file
+    pub fn tokenize(filename: &str, contents: &str, extensions: bool) -> (Vec<Lexicon>, Vec<Diagnostic>) {
+        let source = Source::new(filename, contents);
+        let mut diagnostics = vec![];
+        let (mut lexicon_vec, docs) = Self::scanning(&source, extensions, &mut diagnostics);
+
+        // a doc comment belongs to the class or subroutine declared right after it
+        let declarations = [Keyword::Class, Keyword::Constructor, Keyword::Function, Keyword::Method];
+        for (end, doc) in docs {
+            if let Some(lex) = lexicon_vec.iter_mut().find(|lex| lex.span.start >= end) {
+                if declarations.iter().any(|keyword| lex.token.is_keyword(*keyword)) {
+                    lex.doc = Some(doc);
+                }
+            }
+        }
+        return (lexicon_vec, diagnostics);
+    }
+
+    // scanning the whole file by finite automaton algorithm: returns tokens and doc comments (with the byte index of their end)
+    // after an error the scanning goes on (an invalid literal is replaced by a token of its kind), so that all errors are reported
+    fn scanning(source: &Source, extensions: bool, diagnostics: &mut Vec<Diagnostic>) -> (Vec<Lexicon>, Vec<(usize, String)>) {
+        let symbols = vec!['{', '}', '(', ')', '[', ']', '.', ',', ';', '+', '-', '*', '/', '&', '|', '<', '>', '=', '~'];
+        let mut tokens_vec = vec![];
+        let mut docs = vec![];
+        let mut token = "".to_string();
+        let mut token_start = 0;        // byte index of the token (or '/', '"', '\'') in the file
+        let mut escape_start = 0;       // byte index of '\' in a string constant or a character literal
+        let mut doc_start = 0;          // byte index of the text of a doc comment
+        let mut doc_comment = false;
+        let mut state = 0;
+        for (i, c) in source.text.char_indices() {
+            if state == 0 {
+                // state normal
+                if c == '/' {
+                    if !token.is_empty() {
+                        tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, i), extensions, diagnostics));
+                    }
+                    token = "".to_string();
+                    token_start = i;
+                    state = 1;
+                } else if c == '"' {
+                    if !token.is_empty() {
+                        tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, i), extensions, diagnostics));
+                    }
+                    token = "".to_string();
+                    token_start = i;
+                    state = 2;
+                } else if c == '\'' && extensions {
+                    if !token.is_empty() {
+                        tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, i), extensions, diagnostics));
+                    }
+                    token = "".to_string();
+                    token_start = i;
+                    state = 6;
+                } else if c == '\'' || c == '\\' {
+                    if !token.is_empty() {
+                        tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, i), extensions, diagnostics));
+                    }
+                    token = "".to_string();
+                    if c == '\'' {
+                        Self::error("character literal needs --extensions", &source.span(i, i + 1), diagnostics);
+                    } else {
+                        // escape sequences are only allowed in string constants
+                        Self::error("unexpected character '\\' outside of a string constant", &source.span(i, i + 1), diagnostics);
+                    }
+                } else if c.is_whitespace() {
+                    if !token.is_empty() {
+                        tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, i), extensions, diagnostics));
+                    }
+                    token = "".to_string();
+                } else if symbols.contains(&c) {
+                    if !token.is_empty() {
+                        tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, i), extensions, diagnostics));
+                    }
+                    tokens_vec.push(Self::new(Token::Symbol(c), source.span(i, i + 1)));
+                    token = "".to_string();
+                } else {
+                    if token.is_empty() {
+                        token_start = i;
+                    }
+                    token.push(c);
+                }
+            } else if state == 1 {
+                // state '/'
+                let slash = Self::new(Token::Symbol('/'), source.span(token_start, token_start + 1));
+                if c == '/' {
+                    state = 9;
+                } else if c == '*' {
+                    state = 10;
+                } else if c == '"' {
+                    tokens_vec.push(slash);
+                    token_start = i;
+                    state = 2;
+                } else if c == '\'' && extensions {
+                    tokens_vec.push(slash);
+                    token_start = i;
+                    state = 6;
+                } else if c == '\'' {
+                    tokens_vec.push(slash);
+                    Self::error("character literal needs --extensions", &source.span(i, i + 1), diagnostics);
+                    state = 0;
+                } else if c == '\\' {
+                    tokens_vec.push(slash);
+                    Self::error("unexpected character '\\' outside of a string constant", &source.span(i, i + 1), diagnostics);
+                    state = 0;
+                } else if c.is_whitespace() {
+                    tokens_vec.push(slash);
+                    state = 0;
+                } else if symbols.contains(&c) {
+                    tokens_vec.push(slash);
+                    tokens_vec.push(Self::new(Token::Symbol(c), source.span(i, i + 1)));
+                    state = 0;
+                } else {
+                    tokens_vec.push(slash);
+                    token_start = i;
+                    token.push(c);
+                    state = 0;
+                }
+            } else if state == 2 {
+                // state '"'
+                if c == '"' {
+                    tokens_vec.push(Self::new(Token::StringConst(token), source.span(token_start, i + 1)));
+                    token = "".to_string();
+                    state = 0;
+                } else if c == '\\' {
+                    escape_start = i;
+                    state = 5;
+                } else if c == '\n' {
+                    Self::error("string constant is not terminated", &source.span(token_start, i), diagnostics);
+                    tokens_vec.push(Self::new(Token::StringConst(token), source.span(token_start, i)));
+                    token = "".to_string();
+                    state = 0;
+                } else {
+                    if char_code(c).is_none() {
+                        Self::error(&format!("character '{}' is not in the Hack character set", c), &source.span(i, i + c.len_utf8()), diagnostics);
+                    }
+                    token.push(c);
+                }
+            } else if state == 3 {
+                // state '/*'
+                if c == '*' {
+                    state = 4;
+                }
+            } else if state == 4 {
+                // state '/* *'
+                if c == '/' {
+                    if doc_comment {
+                        let doc = Self::doc_text(&source.text[doc_start..i - 1]);
+                        if !doc.is_empty() {
+                            docs.push((i + 1, doc));
+                        }
+                    }
+                    doc_comment = false;
+                    state = 0;
+                } else if c != '*' {
+                    state = 3;
+                }
+            } else if state == 5 {
+                // state '"\': \n, \t, \\, \"
+                match Self::escape_sequence(c) {
+                    Some(escaped) => token.push(escaped),
+                    None => Self::error(&format!("invalid escape sequence '\\{}': only \\n, \\t, \\\\ and \\\" are supported", c), &source.span(escape_start, i + c.len_utf8()), diagnostics),
+                }
+                state = 2;
+            } else if state == 6 {
+                // state '\'' (extensions)
+                if c == '\\' {
+                    escape_start = i;
+                    state = 7;
+                } else if c == '\'' {
+                    Self::error("empty character literal", &source.span(token_start, i + 1), diagnostics);
+                    tokens_vec.push(Self::new(Token::IntConst(0), source.span(token_start, i + 1)));
+                    state = 0;
+                } else if c == '\n' {
+                    Self::error("character literal is not terminated", &source.span(token_start, i), diagnostics);
+                    tokens_vec.push(Self::new(Token::IntConst(0), source.span(token_start, i)));
+                    state = 0;
+                } else {
+                    token.push(c);
+                    state = 8;
+                }
+            } else if state == 7 {
+                // state '\'\\': \' is also allowed in character literals
+                match Self::escape_sequence(c) {
+                    Some(escaped) => token.push(escaped),
+                    None if c == '\'' => token.push(c),
+                    None => {
+                        Self::error(&format!("invalid escape sequence '\\{}': only \\n, \\t, \\\\, \\\" and \\' are supported", c), &source.span(escape_start, i + c.len_utf8()), diagnostics);
+                        token.push(c);
+                    }
+                }
+                state = 8;
+            } else if state == 8 {
+                // state '\'c': a character literal is the integer constant of its Hack character code
+                let span = source.span(token_start, i + c.len_utf8());
+                let character = token.chars().next().expect("program error: empty character literal");
+                let code = match char_code(character) {
+                    Some(code) => code,
+                    None => {
+                        Self::error(&format!("character '{}' is not in the Hack character set", character), &span, diagnostics);
+                        0
+                    }
+                };
+                tokens_vec.push(Self::new(Token::IntConst(code), span.clone()));
+                token = "".to_string();
+                if c == '\'' {
+                    state = 0;
+                } else if c == '\n' {
+                    Self::error("character literal is not terminated", &source.span(token_start, i), diagnostics);
+                    state = 0;
+                } else {
+                    Self::error("character literal must be a single character", &span, diagnostics);
+                    state = 12;
+                }
+            } else if state == 9 {
+                // state '//'
+                if c == '\n' {
+                    state = 0;
+                }
+            } else if state == 10 {
+                // state '/*': '/**' begins a doc comment
+                if c == '*' {
+                    state = 11;
+                } else {
+                    state = 3;
+                }
+            } else if state == 12 {
+                // state '\'cc': the rest of an invalid character literal is skipped
+                if c == '\'' || c == '\n' {
+                    state = 0;
+                }
+            } else if state == 11 {
+                // state '/**': '/**/' is an empty comment
+                if c == '/' {
+                    state = 0;
+                } else {
+                    doc_comment = true;
+                    doc_start = i;
+                    state = if c == '*' {4} else {3};
+                }
+            }
+        }
+
+        let end = source.text.len();
+        if state == 1 {
+            tokens_vec.push(Self::new(Token::Symbol('/'), source.span(token_start, token_start + 1)));
+        } else if state == 2 || state == 5 {
+            Self::error("string constant is not terminated", &source.span(token_start, end), diagnostics);
+            tokens_vec.push(Self::new(Token::StringConst(token), source.span(token_start, end)));
+        } else if state == 3 || state == 4 || state == 10 || state == 11 {
+            Self::error("comment is not terminated", &source.span(token_start, end), diagnostics);
+        } else if state == 6 || state == 7 || state == 8 {
+            Self::error("character literal is not terminated", &source.span(token_start, end), diagnostics);
+            tokens_vec.push(Self::new(Token::IntConst(0), source.span(token_start, end)));
+        } else if !token.is_empty() {
+            tokens_vec.push(Self::assign_lex_type(token, source.span(token_start, end), extensions, diagnostics));
+        }
+        return (tokens_vec, docs);
+    }
+
+    // text of a doc comment: leading '*' of each line and surrounding blanks are removed
+    fn doc_text(comment: &str) -> String {
+        let mut lines = vec![];
+        for line in comment.lines() {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            lines.push(line.trim());
+        }
+        while lines.first() == Some(&"") {
+            lines.remove(0);
+        }
+        while lines.last() == Some(&"") {
+            lines.pop();
+        }
+        return lines.join("\n");
+    }
+
+    // an invalid integer constant is reported and replaced by 0
+    fn assign_lex_type(token: String, span: Span, extensions: bool, diagnostics: &mut Vec<Diagnostic>) -> Lexicon {
+        if !token.is_empty() {
+            if let Some(keyword) = Keyword::from_word(&token, extensions) {
+                return Self::new(Token::Keyword(keyword), span);
+            } else if token.chars().all(|c| c.is_ascii_digit()) {
+                match token.parse::<u16>() {
+                    Ok(integer) if integer <= MAX_INT_CONST => return Self::new(Token::IntConst(integer), span),
+                    _ => Self::error(&format!("integer constant '{}' is out of range 0..{}", token, MAX_INT_CONST), &span, diagnostics),
+                }
+                return Self::new(Token::IntConst(0), span);
+            } else if token.starts_with("0x") || token.starts_with("0X") {
+                if !extensions {
+                    Self::error(&format!("hex literal '{}' needs --extensions", token), &span, diagnostics);
+                }
+                match u16::from_str_radix(&token[2..], 16) {
+                    Ok(integer) if integer <= MAX_INT_CONST => return Self::new(Token::IntConst(integer), span),
+                    Ok(_) => Self::error(&format!("integer constant '{}' is out of range 0..{}", token, MAX_INT_CONST), &span, diagnostics),
+                    Err(_) => Self::error(&format!("invalid hex literal '{}'", token), &span, diagnostics),
+                }
+                return Self::new(Token::IntConst(0), span);
+            } else {
+                return Self::new(Token::Identifier(token), span);
+            }
+        } else {
+            panic!("program error: invalid function call: in assign_lex_type");
+        }
+    }
+
+    // escape sequences in string constants: "\n" is the newline of the Hack character set (128)
+    fn escape_sequence(c: char) -> Option<char> {
+        match c {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            _ => None,
+        }
+    }
+
+    fn error(message: &str, span: &Span, diagnostics: &mut Vec<Diagnostic>) {
+        diagnostics.push(Diagnostic::error(span, message.to_string()));
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_constants_are_escaped_in_xml() {
+        let token = Token::StringConst("a<b & c > \"d\"".to_string());
+        assert_eq!(token.to_xml(), "<stringConstant> a&lt;b &amp; c &gt; &quot;d&quot; </stringConstant>");
+    }
+
+    #[test]
+    fn string_constants_are_checked_against_the_hack_character_set() {
+        let (lexicons, diagnostics) = Lexicon::tokenize("Main.jack", "let s = \"h\u{e9}llo\";", false);
+        assert_eq!(lexicons.len(), 5);
+        let messages: Vec<String> = diagnostics.iter().map(|diagnostic| format!("{} {}", diagnostic.span.column, diagnostic.message)).collect();
+        assert_eq!(messages, vec!["11 character '\u{e9}' is not in the Hack character set"]);
+    }
+}
@@ -0,0 +1,16 @@
+// front end of jack lang shared by jackanalyzer and jackcompiler
+// コンピュータシステムの理論と実装 §10, §11
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+pub mod ast;
+pub mod control_flow;
+pub mod diagnostic;
+pub mod lexical_analysis;
+pub mod parser;
+pub mod program_info;
+pub mod resolver;
+pub mod symbol_table;
+pub mod type_checker;
+pub mod usage;
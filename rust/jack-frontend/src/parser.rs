@@ -0,0 +1,767 @@
+// parser package
+// parsing jack code via LL(1) analysis into the abstract syntax tree
+// syntax errors are recovered in panic mode: tokens are skipped to ';', '}' or the next statement / declaration
+// operators are evaluated left to right as the Jack spec (or by the conventional precedence with --precedence)
+// コンピュータシステムの理論と実装 §10, §11
+
+use crate::ast::{Class, ClassVarDec, ClassVarKind, Expression, Parameter, Statement, SubroutineCall, SubroutineDec, SubroutineKind, Term, VarDec};
+use crate::diagnostic::Diagnostic;
+use crate::lexical_analysis::{Keyword, Lexicon, Span, Token};
+
+struct Data<'a> {
+    lexicons: &'a Vec<Lexicon>,
+    iter: usize,
+    filename: String,
+    extensions: bool,
+    precedence: bool,
+    // depth of while and for: break and continue are valid in a loop
+    loop_depth: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+// a syntax error has been reported: the caller skips tokens to a synchronising point
+struct SyntaxError;
+
+type Parsed<T> = Result<T, SyntaxError>;
+
+const STATEMENT_KEYWORDS: [Keyword; 8] = [Keyword::Let, Keyword::If, Keyword::While, Keyword::Do, Keyword::Return, Keyword::For, Keyword::Break, Keyword::Continue];
+const DECLARATION_KEYWORDS: [Keyword; 5] = [Keyword::Static, Keyword::Field, Keyword::Constructor, Keyword::Function, Keyword::Method];
+
+// the class (as far as it is parsed) and all syntax errors in the file
+// extensions: for, break, continue and else if (the lexer makes for, break and continue keywords)
+// precedence: expressions are grouped by the conventional precedence of operators
+pub fn parse_class(lexicons: &Vec<Lexicon>, filename: &str, extensions: bool, precedence: bool) -> (Class, Vec<Diagnostic>) {
+    let mut data = Data {lexicons, iter: 0, filename: filename.to_string(), extensions, precedence, loop_depth: 0, diagnostics: vec![]};
+    let class = parse_class_dec(&mut data);
+    return (class, data.diagnostics);
+}
+
+// ---------- class ----------
+// class :: "class" className "{" classVarDec* subroutineDec* "}"
+fn parse_class_dec(data: &mut Data) -> Class {
+    let span = current_span(data);
+    let doc = current_doc(data);
+    let mut class = Class {name: "".to_string(), var_decs: vec![], subroutines: vec![], span, doc};
+    let header = keyword_checker(Keyword::Class, data)
+        .and_then(|_| identifier_checker(data, "a class name"))
+        .and_then(|(name, _)| symbol_checker('{', data).map(|_| name));
+    match header {
+        Ok(name) => class.name = name,
+        Err(_) => return class,
+    }
+
+    // classVarDec* subroutineDec*
+    loop {
+        let result = if keywords_matching(&[Keyword::Static, Keyword::Field], data) {
+            parse_class_var_dec(data).map(|var_dec| class.var_decs.push(var_dec))
+        } else if keywords_matching(&[Keyword::Constructor, Keyword::Function, Keyword::Method], data) {
+            parse_subroutine_dec(data).map(|subroutine| class.subroutines.push(subroutine))
+        } else if symbol_matching('}', data) || is_end(data) {
+            break;
+        } else {
+            Err(error("a class variable or subroutine declaration", data))
+        };
+        if result.is_err() {
+            // skip to the next declaration
+            while !is_end(data) && !keywords_matching(&DECLARATION_KEYWORDS, data) {
+                data.iter += 1;
+            }
+        }
+    }
+
+    let _ = symbol_checker('}', data);
+    if !is_end(data) {
+        error("the end of file", data);
+    }
+    return class;
+}
+
+// classVarDec :: ("static" | "field") type varName ("," varName)* ";"
+fn parse_class_var_dec(data: &mut Data) -> Parsed<ClassVarDec> {
+    let span = current_span(data);
+    let kind = if keywords_checker(&[Keyword::Static, Keyword::Field], data)? == Keyword::Static {
+        ClassVarKind::Static
+    } else {
+        ClassVarKind::Field
+    };
+
+    let var_type = parse_type(data)?;
+    let mut names = vec![identifier_checker(data, "a variable name")?];
+
+    // (, + var_name)*
+    loop {
+        if symbol_matching(',', data) {
+            data.iter += 1;
+            names.push(identifier_checker(data, "a variable name")?);
+        } else {
+            break;
+        }
+    }
+
+    symbol_checker(';', data)?;
+    return Ok(ClassVarDec {kind, var_type, names, span});
+}
+
+fn parse_type(data: &mut Data) -> Parsed<String> {
+    if keyword_matching(Keyword::Int, data) {
+        data.iter += 1;
+        return Ok("int".to_string());
+    } else if keyword_matching(Keyword::Char, data) {
+        data.iter += 1;
+        return Ok("char".to_string());
+    } else if keyword_matching(Keyword::Boolean, data) {
+        data.iter += 1;
+        return Ok("boolean".to_string());
+    } else if let Some(Token::Identifier(class_name)) = current_token(data) {
+        data.iter += 1;
+        return Ok(class_name);
+    } else {
+        return Err(error("a type", data));
+    }
+}
+
+// subroutineDec :: ("constructor" | "function" | "method") ("void" | type ) subroutineName "(" parameterList ")" subroutineBody
+fn parse_subroutine_dec(data: &mut Data) -> Parsed<SubroutineDec> {
+    let span = current_span(data);
+    let doc = current_doc(data);
+    let kind = match keywords_checker(&[Keyword::Constructor, Keyword::Function, Keyword::Method], data)? {
+        Keyword::Constructor => SubroutineKind::Constructor,
+        Keyword::Function => SubroutineKind::Function,
+        _ => SubroutineKind::Method,
+    };
+
+    // ("void" | type )
+    let return_type = if keyword_matching(Keyword::Void, data) {
+        data.iter += 1;
+        "void".to_string()
+    } else {
+        parse_type(data)?
+    };
+
+    let (name, _) = identifier_checker(data, "a subroutine name")?;
+    symbol_checker('(', data)?;
+    let parameters = parse_parameter_list(data)?;
+    symbol_checker(')', data)?;
+
+    // subroutineBody :: "{" varDec* statements "}"
+    symbol_checker('{', data)?;
+    let mut var_decs = vec![];
+    loop {
+        if keyword_matching(Keyword::Var, data) {
+            match parse_var_dec(data) {
+                Ok(var_dec) => var_decs.push(var_dec),
+                Err(_) => synchronize(data),
+            }
+        } else {
+            break;
+        }
+    }
+    let statements = parse_statements(data);
+    symbol_checker('}', data)?;
+
+    return Ok(SubroutineDec {kind, return_type, name, parameters, var_decs, statements, span, doc});
+}
+
+// parameterList :: (type varName ("," type varName)*)?
+fn parse_parameter_list(data: &mut Data) -> Parsed<Vec<Parameter>> {
+    let mut parameters = vec![];
+    if !(symbol_matching(')', data)) {
+        let var_type = parse_type(data)?;
+        let (name, span) = identifier_checker(data, "a parameter name")?;
+        parameters.push(Parameter {var_type, name, span});
+
+        // (, type var_name)*
+        loop {
+            if symbol_matching(',', data) {
+                data.iter += 1;
+                let var_type = parse_type(data)?;
+                let (name, span) = identifier_checker(data, "a parameter name")?;
+                parameters.push(Parameter {var_type, name, span});
+            } else {
+                break;
+            }
+        }
+    }
+    return Ok(parameters);
+}
+
+// varDec :: "var" type varName ("," varName)* ";"
+fn parse_var_dec(data: &mut Data) -> Parsed<VarDec> {
+    let span = current_span(data);
+    keyword_checker(Keyword::Var, data)?;
+    let var_type = parse_type(data)?;
+    let mut names = vec![identifier_checker(data, "a variable name")?];
+
+    // (, var_name)*
+    loop {
+        if symbol_matching(',', data) {
+            data.iter += 1;
+            names.push(identifier_checker(data, "a variable name")?);
+        } else {
+            break;
+        }
+    }
+
+    symbol_checker(';', data)?;
+    return Ok(VarDec {var_type, names, span});
+}
+
+// ---------- statements ----------
+// statements :: (letStatement | ifStatement | whileStatement | doStatement | returnStatement)*
+fn parse_statements(data: &mut Data) -> Vec<Statement> {
+    let mut statements = vec![];
+    loop {
+        let result = if keyword_matching(Keyword::Let, data) {
+            parse_let_statement(data)
+        } else if keyword_matching(Keyword::If, data) {
+            parse_if_statement(data)
+        } else if keyword_matching(Keyword::While, data) {
+            parse_while_statement(data)
+        } else if keyword_matching(Keyword::Do, data) {
+            parse_do_statement(data)
+        } else if keyword_matching(Keyword::Return, data) {
+            parse_return_statement(data)
+        } else if keyword_matching(Keyword::For, data) {
+            parse_for_statement(data)
+        } else if keywords_matching(&[Keyword::Break, Keyword::Continue], data) {
+            parse_break_or_continue_statement(data)
+        } else if symbol_matching('}', data) || is_end(data) || keywords_matching(&DECLARATION_KEYWORDS, data) {
+            break;
+        } else {
+            Err(error("a statement", data))
+        };
+        match result {
+            Ok(statement) => statements.push(statement),
+            Err(_) => synchronize(data),
+        }
+    }
+    return statements;
+}
+
+// skips to the next statement: after ';', or before '}', a statement keyword or a declaration
+// a block "{ ... }" is skipped as a whole, so that its '}' doesn't close the enclosing block
+fn synchronize(data: &mut Data) {
+    let mut depth = 0;
+    while !is_end(data) {
+        if symbol_matching('{', data) {
+            depth += 1;
+        } else if depth != 0 {
+            if symbol_matching('}', data) {
+                depth -= 1;
+            }
+        } else if symbol_matching(';', data) {
+            data.iter += 1;
+            return;
+        } else if symbol_matching('}', data) || keywords_matching(&STATEMENT_KEYWORDS, data) || keywords_matching(&DECLARATION_KEYWORDS, data) {
+            return;
+        }
+        data.iter += 1;
+    }
+}
+
+// letStatement :: "let" varName ("[" expression "]")? "=" expression ";"
+fn parse_let_statement(data: &mut Data) -> Parsed<Statement> {
+    let statement = parse_let(data)?;
+    symbol_checker(';', data)?;
+    return Ok(statement);
+}
+
+// letStatement without ";" (also in for)
+fn parse_let(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    keyword_checker(Keyword::Let, data)?;
+    let (name, _) = identifier_checker(data, "a variable name")?;
+
+    // array: [ expression ]
+    let mut index = None;
+    if symbol_matching('[', data) {
+        data.iter += 1;
+        index = Some(parse_expression(data)?);
+        symbol_checker(']', data)?;
+    }
+
+    symbol_checker('=', data)?;
+    let value = parse_expression(data)?;
+    return Ok(Statement::Let {name, index, value, span});
+}
+
+// ifStatement :: "if" "(" expression ")" "{" statements "}" ("else" "{" statements "}")?
+// --extensions: ("else" ifStatement) too
+fn parse_if_statement(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    keyword_checker(Keyword::If, data)?;
+    symbol_checker('(', data)?;
+    let condition = parse_expression(data)?;
+    symbol_checker(')', data)?;
+    symbol_checker('{', data)?;
+    let then_statements = parse_statements(data);
+    symbol_checker('}', data)?;
+
+    // (else { statements })?
+    let mut else_statements = None;
+    if keyword_matching(Keyword::Else, data) {
+        data.iter += 1;
+        if data.extensions && keyword_matching(Keyword::If, data) {
+            else_statements = Some(vec![parse_if_statement(data)?]);
+        } else {
+            symbol_checker('{', data)?;
+            else_statements = Some(parse_statements(data));
+            symbol_checker('}', data)?;
+        }
+    }
+    return Ok(Statement::If {condition, then_statements, else_statements, span});
+}
+
+// whileStatements :: "while" "(" expression ")" "{" statements "}"
+fn parse_while_statement(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    keyword_checker(Keyword::While, data)?;
+    symbol_checker('(', data)?;
+    let condition = parse_expression(data)?;
+    symbol_checker(')', data)?;
+    symbol_checker('{', data)?;
+    data.loop_depth += 1;
+    let statements = parse_statements(data);
+    data.loop_depth -= 1;
+    symbol_checker('}', data)?;
+    return Ok(Statement::While {condition, statements, span});
+}
+
+// forStatement :: "for" "(" (letStatement | doStatement)? ";" expression? ";" (letStatement | doStatement)? ")" "{" statements "}"
+// (letStatement and doStatement without ";")
+fn parse_for_statement(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    keyword_checker(Keyword::For, data)?;
+    symbol_checker('(', data)?;
+    let init = parse_for_clause(data)?;
+    symbol_checker(';', data)?;
+    let mut condition = None;
+    if !symbol_matching(';', data) {
+        condition = Some(parse_expression(data)?);
+    }
+    symbol_checker(';', data)?;
+    let update = parse_for_clause(data)?;
+    symbol_checker(')', data)?;
+    symbol_checker('{', data)?;
+    data.loop_depth += 1;
+    let statements = parse_statements(data);
+    data.loop_depth -= 1;
+    symbol_checker('}', data)?;
+    return Ok(Statement::For {init, condition, update, statements, span});
+}
+
+fn parse_for_clause(data: &mut Data) -> Parsed<Option<Box<Statement>>> {
+    if keyword_matching(Keyword::Let, data) {
+        return Ok(Some(Box::new(parse_let(data)?)));
+    } else if keyword_matching(Keyword::Do, data) {
+        return Ok(Some(Box::new(parse_do(data)?)));
+    } else if symbol_matching(';', data) || symbol_matching(')', data) {
+        return Ok(None);
+    } else {
+        return Err(error("'let', 'do' or nothing in the for statement", data));
+    }
+}
+
+// breakStatement :: "break" ";"    continueStatement :: "continue" ";"
+fn parse_break_or_continue_statement(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    let keyword = keywords_checker(&[Keyword::Break, Keyword::Continue], data)?;
+    symbol_checker(';', data)?;
+    if data.loop_depth == 0 {
+        data.diagnostics.push(Diagnostic::error(&span, format!("'{}' outside of a loop", keyword.name())).with_hint("use it in while or for"));
+    }
+    if keyword == Keyword::Break {
+        return Ok(Statement::Break {span});
+    } else {
+        return Ok(Statement::Continue {span});
+    }
+}
+
+// doStatements :: "do" subroutineCall ";"
+fn parse_do_statement(data: &mut Data) -> Parsed<Statement> {
+    let statement = parse_do(data)?;
+    symbol_checker(';', data)?;
+    return Ok(statement);
+}
+
+// doStatement without ";" (also in for)
+fn parse_do(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    keyword_checker(Keyword::Do, data)?;
+    let call = parse_subroutine_call(data)?;
+    return Ok(Statement::Do {call, span});
+}
+
+// returnStatements :: "return" expression? ";"
+fn parse_return_statement(data: &mut Data) -> Parsed<Statement> {
+    let span = current_span(data);
+    keyword_checker(Keyword::Return, data)?;
+    let mut value = None;
+    if !(symbol_matching(';', data)) {
+        value = Some(parse_expression(data)?);
+    }
+    symbol_checker(';', data)?;
+    return Ok(Statement::Return {value, span});
+}
+
+// ---------- expression ----------
+// subroutineCall :: ((className | varName) ".")? subroutineName "(" expressionList ")"
+fn parse_subroutine_call(data: &mut Data) -> Parsed<SubroutineCall> {
+    let span = current_span(data);
+    let (name, _) = identifier_checker(data, "a subroutine call")?;
+
+    // subroutineName "(" expressionList ")"
+    if symbol_matching('(', data) {
+        let arguments = parse_expression_list(data)?;
+        return Ok(SubroutineCall {receiver: None, name, arguments, span});
+
+    // (className | varName) "." subroutineName "(" expressionList ")"
+    } else if symbol_matching('.', data) {
+        data.iter += 1;
+        let (subroutine_name, _) = identifier_checker(data, "a subroutine name")?;
+        let arguments = parse_expression_list(data)?;
+        return Ok(SubroutineCall {receiver: Some(name), name: subroutine_name, arguments, span});
+    } else {
+        return Err(error("'(' or '.' of a subroutine call", data));
+    }
+}
+
+// "(" expressionList ")" :: "(" (expression ("," expression)*)? ")"
+fn parse_expression_list(data: &mut Data) -> Parsed<Vec<Expression>> {
+    symbol_checker('(', data)?;
+    let mut arguments = vec![];
+    if symbol_matching(')', data) {
+        data.iter += 1;
+    } else {
+        arguments.push(parse_expression(data)?);
+        // ("," expression)* ")"
+        loop {
+            if symbol_matching(')', data) {
+                data.iter += 1;
+                break;
+            } else if symbol_matching(',', data) {
+                data.iter += 1;
+                arguments.push(parse_expression(data)?);
+            } else {
+                return Err(error("',' or ')' in the argument list", data));
+            }
+        }
+    }
+    return Ok(arguments);
+}
+
+// expression :: term (op term)*
+fn parse_expression(data: &mut Data) -> Parsed<Expression> {
+    let span = current_span(data);
+    let term = parse_term(data)?;
+    let mut operations = vec![];
+    let mut operator_spans = vec![];
+    loop {
+        if let Some(Token::Symbol(operator)) = current_token(data) {
+            if "+-*/&|<>=".contains(operator) {
+                operator_spans.push(current_span(data));
+                data.iter += 1;
+                operations.push((operator, parse_term(data)?));
+                continue;
+            }
+        }
+        break;
+    }
+    if data.precedence {
+        return Ok(group_by_precedence(term, operations, span));
+    }
+
+    // left to right: a + b * c is (a + b) * c
+    for i in 1..operations.len() {
+        if precedence(operations[i].0) > precedence(operations[i - 1].0) {
+            let message = format!("'{}' after '{}' is evaluated left to right, not by precedence", operations[i].0, operations[i - 1].0);
+            data.diagnostics.push(Diagnostic::warning(&operator_spans[i], message).with_hint("add parentheses, or compile with --precedence"));
+            break;
+        }
+    }
+    return Ok(Expression {term, operations, span});
+}
+
+// * / over + - over < > = over & |
+fn precedence(operator: char) -> usize {
+    if operator == '*' || operator == '/' {
+        return 3;
+    } else if operator == '+' || operator == '-' {
+        return 2;
+    } else if operator == '<' || operator == '>' || operator == '=' {
+        return 1;
+    } else {
+        return 0;
+    }
+}
+
+// term (op term)* -> the operators of the lowest precedence with the other parts in parentheses:
+// a + b * c - d is a + (b * c) - d, and the vm code evaluates it left to right as before
+fn group_by_precedence(term: Term, operations: Vec<(char, Term)>, span: Span) -> Expression {
+    let lowest = match operations.iter().map(|(operator, _)| precedence(*operator)).min() {
+        Some(lowest) => lowest,
+        None => return Expression {term, operations, span},
+    };
+    let mut parts = vec![(term, vec![])];
+    let mut operators = vec![];
+    for (operator, term) in operations {
+        if precedence(operator) == lowest {
+            operators.push(operator);
+            parts.push((term, vec![]));
+        } else {
+            parts.last_mut().expect("parts are not empty").1.push((operator, term));
+        }
+    }
+    let mut terms = parts.into_iter().map(|(term, operations)| {
+        if operations.is_empty() {
+            return term;
+        }
+        let span = term.span().clone();
+        return Term::Parenthesized(Box::new(group_by_precedence(term, operations, span.clone())), span);
+    });
+    let term = terms.next().expect("parts are not empty");
+    return Expression {term, operations: operators.into_iter().zip(terms).collect(), span};
+}
+
+// term :: integerConstant | stringConstant | keywordConstant | varName | varName "[" expression "]" | "(" expression ")" | unaryOp term | subroutineCall
+fn parse_term(data: &mut Data) -> Parsed<Term> {
+    let span = current_span(data);
+    match current_token(data) {
+        // integerConstant
+        Some(Token::IntConst(integer)) => {
+            data.iter += 1;
+            return Ok(Term::IntConst(integer, span));
+        }
+        // stringConstant
+        Some(Token::StringConst(string)) => {
+            data.iter += 1;
+            return Ok(Term::StringConst(string, span));
+        }
+        // keywordConstant
+        Some(Token::Keyword(keyword)) if matches!(keyword, Keyword::True | Keyword::False | Keyword::Null | Keyword::This) => {
+            data.iter += 1;
+            return Ok(Term::KeywordConst(keyword, span));
+        }
+        // unaryOp term
+        Some(Token::Symbol(operator)) if operator == '-' || operator == '~' => {
+            data.iter += 1;
+            let term = parse_term(data)?;
+            return Ok(Term::Unary(operator, Box::new(term), span));
+        }
+        // "(" expression ")"
+        Some(Token::Symbol('(')) => {
+            data.iter += 1;
+            let expression = parse_expression(data)?;
+            symbol_checker(')', data)?;
+            return Ok(Term::Parenthesized(Box::new(expression), span));
+        }
+        Some(Token::Identifier(name)) => {
+            let next = data.lexicons.get(data.iter + 1).map(|lex| &lex.token);
+            // varName "[" expression "]"
+            if next == Some(&Token::Symbol('[')) {
+                data.iter += 2;
+                let expression = parse_expression(data)?;
+                symbol_checker(']', data)?;
+                return Ok(Term::ArrayElement(name, Box::new(expression), span));
+
+            // subroutineCall :: ((className | varName) ".")? subroutineName "(" expressionList ")"
+            } else if next == Some(&Token::Symbol('.')) || next == Some(&Token::Symbol('(')) {
+                return Ok(Term::Call(parse_subroutine_call(data)?));
+            }
+            // varName
+            data.iter += 1;
+            return Ok(Term::Var(name, span));
+        }
+        _ => return Err(error("an expression", data)),
+    }
+}
+
+// subroutine
+// reports "expected ... but found ..." at the current token
+fn error(expected: &str, data: &mut Data) -> SyntaxError {
+    let span = current_span(data);
+    return error_at(expected, span, data);
+}
+
+fn error_at(expected: &str, span: Span, data: &mut Data) -> SyntaxError {
+    let message = match data.lexicons.get(data.iter) {
+        Some(lex) => format!("expected {} but found {}", expected, describe(&lex.token)),
+        None => format!("expected {} but reached the end of file", expected),
+    };
+    // after a syntax error, the rest of the file may be skipped to the end: only the first "end of file" is reported
+    let at_end = is_end(data);
+    if !(at_end && data.diagnostics.iter().any(|diagnostic| diagnostic.is_error())) {
+        data.diagnostics.push(Diagnostic::error(&span, message));
+    }
+    return SyntaxError;
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Keyword(keyword) => format!("keyword '{}'", keyword.name()),
+        Token::Symbol(symbol) => format!("'{}'", symbol),
+        Token::IntConst(integer) => format!("integer constant {}", integer),
+        Token::StringConst(string) => format!("string constant \"{}\"", string),
+        Token::Identifier(name) => format!("identifier '{}'", name),
+    }
+}
+
+fn is_end(data: &Data) -> bool {
+    return data.lexicons.len() <= data.iter;
+}
+
+fn keyword_matching(keyword: Keyword, data: &Data) -> bool {
+    return current_token(data) == Some(Token::Keyword(keyword));
+}
+
+fn keywords_matching(keywords: &[Keyword], data: &Data) -> bool {
+    return keywords.iter().any(|keyword| keyword_matching(*keyword, data));
+}
+
+fn symbol_matching(symbol: char, data: &Data) -> bool {
+    return current_token(data) == Some(Token::Symbol(symbol));
+}
+
+fn current_token(data: &Data) -> Option<Token> {
+    return data.lexicons.get(data.iter).map(|lex| lex.token.clone());
+}
+
+// span of the current token: just after the last token at the end of file
+fn current_span(data: &Data) -> Span {
+    if let Some(lex) = data.lexicons.get(data.iter) {
+        return lex.span.clone();
+    } else if !data.lexicons.is_empty() {
+        return previous_end_span(data);
+    } else {
+        return Span {file: data.filename.to_string(), line: 1, column: 1, start: 0, end: 0};
+    }
+}
+
+// empty span just after the previous token
+fn previous_end_span(data: &Data) -> Span {
+    let mut span = data.lexicons[data.iter.min(data.lexicons.len()) - 1].span.clone();
+    span.column += span.end - span.start;
+    span.start = span.end;
+    return span;
+}
+
+// doc comment /** */ attached to the current token
+fn current_doc(data: &Data) -> Option<String> {
+    return data.lexicons.get(data.iter).and_then(|lex| lex.doc.clone());
+}
+
+fn keyword_checker(keyword: Keyword, data: &mut Data) -> Parsed<()> {
+    return token_checker(Token::Keyword(keyword), data);
+}
+
+fn symbol_checker(symbol: char, data: &mut Data) -> Parsed<()> {
+    return token_checker(Token::Symbol(symbol), data);
+}
+
+// token: a keyword or a symbol
+fn token_checker(token: Token, data: &mut Data) -> Parsed<()> {
+    if current_token(data) == Some(token.clone()) {
+        data.iter += 1;
+        return Ok(());
+    } else if data.iter != 0 && (is_end(data) || data.lexicons[data.iter - 1].span.line != data.lexicons[data.iter].span.line) {
+        // a missing token at the end of a line (e.g. ';') is reported just after the previous token
+        let span = previous_end_span(data);
+        return Err(error_at(&format!("'{}'", token.text()), span, data));
+    } else {
+        return Err(error(&format!("'{}'", token.text()), data));
+    }
+}
+
+fn keywords_checker(keywords: &[Keyword], data: &mut Data) -> Parsed<Keyword> {
+    for keyword in keywords {
+        if keyword_matching(*keyword, data) {
+            data.iter += 1;
+            return Ok(*keyword);
+        }
+    }
+    let expected: Vec<String> = keywords.iter().map(|keyword| format!("'{}'", keyword.name())).collect();
+    return Err(error(&expected.join(" or "), data));
+}
+
+// expected: what the identifier is, e.g. "a variable name"
+fn identifier_checker(data: &mut Data, expected: &str) -> Parsed<(String, Span)> {
+    if let Some(Token::Identifier(name)) = current_token(data) {
+        let span = current_span(data);
+        data.iter += 1;
+        return Ok((name, span));
+    } else {
+        return Err(error(expected, data));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the expression of "return expression;" in "class Main { function int f(int a, int b, int c) {...} }"
+    fn parse_return(expression: &str, precedence: bool) -> (Expression, Vec<Diagnostic>) {
+        let source = format!("class Main {{\nfunction int f(int a, int b, int c) {{\nreturn {};\n}}\n}}\n", expression);
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", &source, false);
+        assert!(lexical_errors.is_empty(), "invalid test code");
+        let (mut class, diagnostics) = parse_class(&lexicons, "Main.jack", false, precedence);
+        return match class.subroutines.remove(0).statements.remove(0) {
+            Statement::Return {value: Some(value), ..} => (value, diagnostics),
+            _ => panic!("invalid test code"),
+        };
+    }
+
+    // the expression with its grouping made explicit by parentheses
+    fn to_string(expression: &Expression) -> String {
+        let mut string = term_to_string(&expression.term);
+        for (operator, term) in &expression.operations {
+            string += &format!(" {} {}", operator, term_to_string(term));
+        }
+        return string;
+    }
+
+    fn term_to_string(term: &Term) -> String {
+        return match term {
+            Term::IntConst(value, _) => value.to_string(),
+            Term::Var(name, _) => name.to_string(),
+            Term::Parenthesized(expression, _) => format!("({})", to_string(expression)),
+            _ => panic!("unexpected term in test code"),
+        };
+    }
+
+    #[test]
+    fn precedence_groups_multiplication_first() {
+        let (expression, diagnostics) = parse_return("1 + 2 * 3", true);
+        assert_eq!(to_string(&expression), "1 + (2 * 3)");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn left_to_right_without_precedence() {
+        let (expression, _) = parse_return("1 + 2 * 3", false);
+        assert_eq!(to_string(&expression), "1 + 2 * 3");
+    }
+
+    #[test]
+    fn no_warning_when_left_to_right_is_conventional() {
+        let (_, diagnostics) = parse_return("a * b + c", false);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn mixed_operators_warn_once_at_the_operator() {
+        let (_, diagnostics) = parse_return("a + b * c", false);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].is_error());
+        assert_eq!((diagnostics[0].span.line, diagnostics[0].span.column), (3, 14));
+        assert_eq!(diagnostics[0].message, "'*' after '+' is evaluated left to right, not by precedence");
+    }
+
+    #[test]
+    fn break_and_continue_outside_of_a_loop_are_errors() {
+        let source = "class Main {\nfunction void f() {\nbreak;\nwhile (true) { if (true) { continue; } }\ncontinue;\n}\n}\n";
+        let (lexicons, _) = Lexicon::tokenize("Main.jack", source, true);
+        let (_, diagnostics) = parse_class(&lexicons, "Main.jack", true, false);
+        let messages: Vec<String> = diagnostics.iter().map(|diagnostic| format!("{} {}", diagnostic.span.line, diagnostic.message)).collect();
+        assert_eq!(messages, vec!["3 'break' outside of a loop", "5 'continue' outside of a loop"]);
+    }
+}
@@ -0,0 +1,113 @@
+// signatures of the subroutines of all classes in a program: collected before semantic analysis
+// so that calls to other classes can be checked
+
+use std::collections::HashMap;
+use crate::ast::{Class, SubroutineKind};
+
+pub struct SubroutineInfo {
+    pub kind: SubroutineKind,
+    pub return_type: String,
+    pub parameter_types: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ProgramInfo {
+    // class name -> subroutine name -> signature
+    pub classes: HashMap<String, HashMap<String, SubroutineInfo>>,
+}
+
+impl ProgramInfo {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    // the Jack OS classes: classes of the program with the same name replace them
+    pub fn add_os_api(&mut self) {
+        for (class_name, kind, return_type, subroutine_name, parameter_types) in OS_API {
+            let kind = match kind {
+                "constructor" => SubroutineKind::Constructor,
+                "function" => SubroutineKind::Function,
+                _ => SubroutineKind::Method,
+            };
+            let parameter_types = parameter_types.iter().map(|parameter_type| parameter_type.to_string()).collect();
+            let info = SubroutineInfo {kind, return_type: return_type.to_string(), parameter_types};
+            self.classes.entry(class_name.to_string()).or_default().insert(subroutine_name.to_string(), info);
+        }
+    }
+
+    pub fn add_class(&mut self, class: &Class) {
+        let mut subroutines = HashMap::new();
+        for subroutine in &class.subroutines {
+            let parameter_types = subroutine.parameters.iter().map(|parameter| parameter.var_type.to_string()).collect();
+            subroutines.insert(subroutine.name.to_string(), SubroutineInfo {kind: subroutine.kind, return_type: subroutine.return_type.to_string(), parameter_types});
+        }
+        self.classes.insert(class.name.to_string(), subroutines);
+    }
+
+    pub fn has_class(&self, class_name: &str) -> bool {
+        return self.classes.contains_key(class_name);
+    }
+
+    // receiver of a call which is not a variable: a known class, or a class of another file (class names begin with an uppercase letter)
+    pub fn is_class_name(&self, name: &str) -> bool {
+        return self.has_class(name) || name.starts_with(|c: char| c.is_ascii_uppercase());
+    }
+
+    pub fn find(&self, class_name: &str, subroutine_name: &str) -> Option<&SubroutineInfo> {
+        return self.classes.get(class_name).and_then(|subroutines| subroutines.get(subroutine_name));
+    }
+}
+
+// the Jack OS API: (class, kind, return type, subroutine, parameter types)
+// コンピュータシステムの理論と実装 §9.2, §12
+const OS_API: [(&str, &str, &str, &str, &[&str]); 49] = [
+    ("Math", "function", "void", "init", &[]),
+    ("Math", "function", "int", "abs", &["int"]),
+    ("Math", "function", "int", "multiply", &["int", "int"]),
+    ("Math", "function", "int", "divide", &["int", "int"]),
+    ("Math", "function", "int", "min", &["int", "int"]),
+    ("Math", "function", "int", "max", &["int", "int"]),
+    ("Math", "function", "int", "sqrt", &["int"]),
+    ("String", "constructor", "String", "new", &["int"]),
+    ("String", "method", "void", "dispose", &[]),
+    ("String", "method", "int", "length", &[]),
+    ("String", "method", "char", "charAt", &["int"]),
+    ("String", "method", "void", "setCharAt", &["int", "char"]),
+    ("String", "method", "String", "appendChar", &["char"]),
+    ("String", "method", "void", "eraseLastChar", &[]),
+    ("String", "method", "int", "intValue", &[]),
+    ("String", "method", "void", "setInt", &["int"]),
+    ("String", "function", "char", "backSpace", &[]),
+    ("String", "function", "char", "doubleQuote", &[]),
+    ("String", "function", "char", "newLine", &[]),
+    ("Array", "function", "Array", "new", &["int"]),
+    ("Array", "method", "void", "dispose", &[]),
+    ("Output", "function", "void", "init", &[]),
+    ("Output", "function", "void", "moveCursor", &["int", "int"]),
+    ("Output", "function", "void", "printChar", &["char"]),
+    ("Output", "function", "void", "printString", &["String"]),
+    ("Output", "function", "void", "printInt", &["int"]),
+    ("Output", "function", "void", "println", &[]),
+    ("Output", "function", "void", "backSpace", &[]),
+    ("Screen", "function", "void", "init", &[]),
+    ("Screen", "function", "void", "clearScreen", &[]),
+    ("Screen", "function", "void", "setColor", &["boolean"]),
+    ("Screen", "function", "void", "drawPixel", &["int", "int"]),
+    ("Screen", "function", "void", "drawLine", &["int", "int", "int", "int"]),
+    ("Screen", "function", "void", "drawRectangle", &["int", "int", "int", "int"]),
+    ("Screen", "function", "void", "drawCircle", &["int", "int", "int"]),
+    ("Keyboard", "function", "void", "init", &[]),
+    ("Keyboard", "function", "char", "keyPressed", &[]),
+    ("Keyboard", "function", "char", "readChar", &[]),
+    ("Keyboard", "function", "String", "readLine", &["String"]),
+    ("Keyboard", "function", "int", "readInt", &["String"]),
+    ("Memory", "function", "void", "init", &[]),
+    ("Memory", "function", "int", "peek", &["int"]),
+    ("Memory", "function", "void", "poke", &["int", "int"]),
+    ("Memory", "function", "Array", "alloc", &["int"]),
+    ("Memory", "function", "void", "deAlloc", &["Array"]),
+    ("Sys", "function", "void", "init", &[]),
+    ("Sys", "function", "void", "halt", &[]),
+    ("Sys", "function", "void", "error", &["int"]),
+    ("Sys", "function", "void", "wait", &["int"]),
+];
@@ -0,0 +1,312 @@
+// semantic analysis: resolution of class names and subroutine calls against the signatures of the whole program
+// undefined classes are reported only when the whole program is known (e.g. compiling a directory)
+// a function has no 'this': fields, 'this' and methods of the class can't be used in it
+
+use std::collections::{HashMap, HashSet};
+use crate::ast::{Class, ClassVarKind, Expression, Statement, SubroutineCall, SubroutineDec, SubroutineKind, Term};
+use crate::diagnostic::Diagnostic;
+use crate::lexical_analysis::{Keyword, Span};
+use crate::program_info::ProgramInfo;
+
+struct Data<'a> {
+    class: &'a Class,
+    program_info: &'a ProgramInfo,
+    whole_program: bool,
+    class_vars: HashMap<String, String>,
+    fields: HashSet<String>,
+    local_vars: HashMap<String, String>,
+    subroutine: Option<&'a SubroutineDec>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub fn resolve_class(class: &Class, program_info: &ProgramInfo, whole_program: bool) -> Vec<Diagnostic> {
+    let mut data = Data {class, program_info, whole_program, class_vars: HashMap::new(), fields: HashSet::new(), local_vars: HashMap::new(), subroutine: None, diagnostics: vec![]};
+
+    // the class name, static and field variables and subroutines share the names of the class
+    let mut class_names = HashSet::from([class.name.to_string()]);
+    for var_dec in &class.var_decs {
+        check_type(&var_dec.var_type, &var_dec.span, &mut data);
+        for (name, span) in &var_dec.names {
+            check_duplicate(name, span, &mut class_names, &format!("class '{}'", class.name), &mut data);
+            data.class_vars.insert(name.to_string(), var_dec.var_type.to_string());
+            if var_dec.kind == ClassVarKind::Field {
+                data.fields.insert(name.to_string());
+            }
+        }
+    }
+    for subroutine in &class.subroutines {
+        check_duplicate(&subroutine.name, &subroutine.span, &mut class_names, &format!("class '{}'", class.name), &mut data);
+    }
+
+    for subroutine in &class.subroutines {
+        if subroutine.return_type != "void" {
+            check_type(&subroutine.return_type, &subroutine.span, &mut data);
+        }
+        // parameters and local variables share the names of the subroutine
+        let mut local_names = HashSet::new();
+        data.local_vars = HashMap::new();
+        for parameter in &subroutine.parameters {
+            check_type(&parameter.var_type, &parameter.span, &mut data);
+            check_duplicate(&parameter.name, &parameter.span, &mut local_names, &format!("subroutine '{}'", subroutine.name), &mut data);
+            data.local_vars.insert(parameter.name.to_string(), parameter.var_type.to_string());
+        }
+        for var_dec in &subroutine.var_decs {
+            check_type(&var_dec.var_type, &var_dec.span, &mut data);
+            for (name, span) in &var_dec.names {
+                check_duplicate(name, span, &mut local_names, &format!("subroutine '{}'", subroutine.name), &mut data);
+                data.local_vars.insert(name.to_string(), var_dec.var_type.to_string());
+            }
+        }
+        data.subroutine = Some(subroutine);
+        resolve_statements(&subroutine.statements, &mut data);
+    }
+    return data.diagnostics;
+}
+
+fn check_type(var_type: &str, span: &Span, data: &mut Data) {
+    if ["int", "char", "boolean"].contains(&var_type) {
+        return;
+    }
+    if data.whole_program && !data.program_info.has_class(var_type) {
+        data.diagnostics.push(Diagnostic::error(span, format!("class '{}' is not defined", var_type)));
+    }
+}
+
+// scope: "class 'Foo'" or "subroutine 'bar'" for the message
+fn check_duplicate(name: &str, span: &Span, names: &mut HashSet<String>, scope: &str, data: &mut Data) {
+    if !names.insert(name.to_string()) {
+        let message = format!("'{}' is already declared in {}", name, scope);
+        data.diagnostics.push(Diagnostic::error(span, message).with_hint("rename one of them"));
+    }
+}
+
+// ---------- statements ----------
+fn resolve_statements(statements: &Vec<Statement>, data: &mut Data) {
+    for statement in statements {
+        resolve_statement(statement, data);
+    }
+}
+
+fn resolve_statement(statement: &Statement, data: &mut Data) {
+    match statement {
+        Statement::Let {name, index, value, span} => {
+            check_field_use(name, span, data);
+            if let Some(index) = index {
+                resolve_expression(index, data);
+            }
+            resolve_expression(value, data);
+        }
+        Statement::If {condition, then_statements, else_statements, ..} => {
+            resolve_expression(condition, data);
+            resolve_statements(then_statements, data);
+            if let Some(else_statements) = else_statements {
+                resolve_statements(else_statements, data);
+            }
+        }
+        Statement::While {condition, statements, ..} => {
+            resolve_expression(condition, data);
+            resolve_statements(statements, data);
+        }
+        Statement::Do {call, ..} => {
+            resolve_subroutine_call(call, data);
+        }
+        Statement::Return {value, ..} => {
+            if let Some(value) = value {
+                resolve_expression(value, data);
+            }
+        }
+        Statement::For {init, condition, update, statements, ..} => {
+            if let Some(init) = init {
+                resolve_statement(init, data);
+            }
+            if let Some(condition) = condition {
+                resolve_expression(condition, data);
+            }
+            if let Some(update) = update {
+                resolve_statement(update, data);
+            }
+            resolve_statements(statements, data);
+        }
+        Statement::Break {..} | Statement::Continue {..} => {}
+    }
+}
+
+// ---------- expression ----------
+fn resolve_expression(expression: &Expression, data: &mut Data) {
+    resolve_term(&expression.term, data);
+    for (_, term) in &expression.operations {
+        resolve_term(term, data);
+    }
+}
+
+fn resolve_term(term: &Term, data: &mut Data) {
+    match term {
+        Term::KeywordConst(Keyword::This, span) => {
+            if let Some(subroutine) = in_function(data) {
+                let message = format!("'this' is used in function '{}'", subroutine.name);
+                data.diagnostics.push(Diagnostic::error(span, message).with_hint("a function has no object: declare it as a method"));
+            }
+        }
+        Term::Var(name, span) => {
+            check_field_use(name, span, data);
+        }
+        Term::ArrayElement(name, index, span) => {
+            check_field_use(name, span, data);
+            resolve_expression(index, data);
+        }
+        Term::Parenthesized(expression, _) => resolve_expression(expression, data),
+        Term::Unary(_, term, _) => resolve_term(term, data),
+        Term::Call(call) => resolve_subroutine_call(call, data),
+        _ => {}
+    }
+}
+
+fn resolve_subroutine_call(call: &SubroutineCall, data: &mut Data) {
+    for argument in &call.arguments {
+        resolve_expression(argument, data);
+    }
+
+    // subroutineName(...) of this class, varName.subroutineName(...) or className.subroutineName(...)
+    // on_object: None for this class, Some(true) for a variable, Some(false) for a class name
+    let (class_name, on_object) = match &call.receiver {
+        None => (data.class.name.to_string(), None),
+        Some(receiver) if !check_field_use(receiver, &call.span, data) => return,
+        Some(receiver) => match variable_type(receiver, data) {
+            // int, char and boolean have no methods: the call can't be compiled whatever the type-check level
+            Some(var_type) if ["int", "char", "boolean"].contains(&var_type.as_str()) => {
+                let message = format!("method '{}' is called on '{}' of type {}", call.name, receiver, var_type);
+                data.diagnostics.push(Diagnostic::error(&call.span, message).with_hint("int, char and boolean have no methods"));
+                return;
+            }
+            Some(var_type) => (var_type, Some(true)),
+            // an undefined variable is reported by the type checker
+            None if !data.program_info.is_class_name(receiver) => return,
+            None => (receiver.to_string(), Some(false)),
+        },
+    };
+    if !data.program_info.has_class(&class_name) {
+        if data.whole_program {
+            data.diagnostics.push(Diagnostic::error(&call.span, format!("class '{}' is not defined", class_name)));
+        }
+        return;
+    }
+    let info = match data.program_info.find(&class_name, &call.name) {
+        Some(info) => info,
+        None => {
+            data.diagnostics.push(Diagnostic::error(&call.span, format!("subroutine '{}.{}' is not defined", class_name, call.name)));
+            return;
+        }
+    };
+
+    let name = format!("{}.{}", class_name, call.name);
+    if on_object.is_none() && info.kind == SubroutineKind::Method {
+        if let Some(subroutine) = in_function(data) {
+            let message = format!("method '{}' is called in function '{}' which has no object", name, subroutine.name);
+            data.diagnostics.push(Diagnostic::error(&call.span, message).with_hint("declare the caller as a method, or call the method on an object"));
+        }
+    } else if on_object == Some(true) && info.kind != SubroutineKind::Method {
+        let diagnostic = Diagnostic::error(&call.span, format!("{} '{}' is called on an object", info.kind.name(), name));
+        data.diagnostics.push(diagnostic.with_hint(&format!("call it as {}(...)", name)));
+    } else if on_object == Some(false) && info.kind == SubroutineKind::Method {
+        let diagnostic = Diagnostic::error(&call.span, format!("method '{}' is called as a function", name));
+        data.diagnostics.push(diagnostic.with_hint(&format!("call it on an object of {}: obj.{}(...)", class_name, call.name)));
+    }
+    if info.parameter_types.len() != call.arguments.len() {
+        data.diagnostics.push(Diagnostic::error(&call.span, format!("'{}' takes {} argument(s) but {} are given", name, info.parameter_types.len(), call.arguments.len())));
+    }
+}
+
+// subroutine
+// the current subroutine if it is a function
+fn in_function<'a>(data: &Data<'a>) -> Option<&'a SubroutineDec> {
+    return data.subroutine.filter(|subroutine| subroutine.kind == SubroutineKind::Function);
+}
+
+// a field (not hidden by a local variable) can't be used in a function: returns false if it is reported
+fn check_field_use(name: &str, span: &Span, data: &mut Data) -> bool {
+    if data.local_vars.contains_key(name) || !data.fields.contains(name) {
+        return true;
+    }
+    if let Some(subroutine) = in_function(data) {
+        let message = format!("field '{}' is used in function '{}'", name, subroutine.name);
+        data.diagnostics.push(Diagnostic::error(span, message).with_hint("a function has no object: declare it as a method"));
+        return false;
+    }
+    return true;
+}
+
+fn variable_type(name: &str, data: &Data) -> Option<String> {
+    if let Some(var_type) = data.local_vars.get(name) {
+        return Some(var_type.to_string());
+    } else if let Some(var_type) = data.class_vars.get(name) {
+        return Some(var_type.to_string());
+    } else {
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexical_analysis::Lexicon;
+    use crate::parser::parse_class;
+
+    // diagnostics of the resolution of "class Main { body }" (the only class with the Jack OS)
+    fn resolve(body: &str) -> Vec<String> {
+        let source = format!("class Main {{\n{}\n}}\n", body);
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", &source, false);
+        let (class, syntax_errors) = parse_class(&lexicons, "Main.jack", false, false);
+        assert!(lexical_errors.is_empty() && syntax_errors.is_empty(), "invalid test code");
+        let mut program_info = ProgramInfo::new();
+        program_info.add_os_api();
+        program_info.add_class(&class);
+        return resolve_class(&class, &program_info, true).iter().map(|diagnostic| format!("{} {}", diagnostic.span.line, diagnostic.message)).collect();
+    }
+
+    #[test]
+    fn method_call_on_primitive_is_an_error() {
+        let messages = resolve("function void f() {
+            var int x;
+            do x.foo();
+            return;
+        }");
+        assert_eq!(messages, vec!["4 method 'foo' is called on 'x' of type int"]);
+    }
+
+    #[test]
+    fn duplicate_declarations_are_errors() {
+        let messages = resolve("field int size;
+            method void size() { return; }
+            function void f(int x) {
+                var int y, y;
+                var boolean x;
+                return;
+            }");
+        assert_eq!(messages, vec![
+            "3 'size' is already declared in class 'Main'",
+            "5 'y' is already declared in subroutine 'f'",
+            "6 'x' is already declared in subroutine 'f'",
+        ]);
+    }
+
+    #[test]
+    fn local_variable_can_hide_a_field() {
+        let messages = resolve("field int size;
+            method void f() {
+                var int size;
+                let size = 1;
+                return;
+            }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn method_called_as_a_function_is_an_error() {
+        let messages = resolve("method void m() { return; }
+            method void f() {
+                do Main.m();
+                return;
+            }");
+        assert_eq!(messages, vec!["4 method 'Main.m' is called as a function"]);
+    }
+}
@@ -0,0 +1,80 @@
+// symbol table of a jack class: class/subroutine names, static, field, var and arg
+// コンピュータシステムの理論と実装 §11
+
+use std::collections::HashMap;
+
+pub struct SymbolInfo {
+    pub category: String,
+    pub var_type: String,
+    pub index: String,
+}
+
+impl SymbolInfo {
+    fn new(category: &String, var_type: &String, index: &String) -> Self {
+        SymbolInfo {category: category.to_string(), var_type: var_type.to_string(), index: index.to_string()}
+    }
+}
+
+#[derive(Default)]
+pub struct SymbolTable {
+    global: HashMap<String, SymbolInfo>,
+    local: HashMap<String, SymbolInfo>,
+    static_counter: usize,
+    field_counter: usize,
+    pub var_counter: usize,
+    arg_counter: usize,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    // returns the segment and index ("local 0"), or an error message if the name is already declared in the scope
+    pub fn define(&mut self, name: &String, category: &str, var_type: &String) -> Result<String, String> {
+        let is_local = category == "var" || category == "arg";
+        if (is_local && self.local.contains_key(name)) || (!is_local && self.global.contains_key(name)) {
+            return Err(format!("identifier '{}' is already declared", name));
+        }
+        let index = if category == "class" || category == "subroutine" {
+            "none".to_string()
+        } else if category == "static" {
+            self.static_counter += 1;
+            format!("static {}", self.static_counter - 1)
+        } else if category == "field" {
+            self.field_counter += 1;
+            format!("this {}", self.field_counter - 1)
+        } else if category == "var" {
+            self.var_counter += 1;
+            format!("local {}", self.var_counter - 1)
+        } else if category == "arg" {
+            self.arg_counter += 1;
+            format!("argument {}", self.arg_counter - 1)
+        } else {
+            return Err(format!("invalid identifier category '{}'", category));
+        };
+        let symbol_info = SymbolInfo::new(&category.to_string(), var_type, &index);
+        if is_local {
+            self.local.insert(name.to_string(), symbol_info);
+        } else {
+            self.global.insert(name.to_string(), symbol_info);
+        }
+        return Ok(index);
+    }
+
+    pub fn find(&self, name: &String) -> Option<&SymbolInfo> {
+        if let Some(info) = self.local.get(name) {
+            return Some(info);
+        } else if let Some(info) = self.global.get(name) {
+            return Some(info);
+        } else {
+            return None;
+        }
+    }
+
+    pub fn clear_local_table(&mut self) {
+        self.local.clear();
+        self.var_counter = 0;
+        self.arg_counter = 0;
+    }
+}
@@ -0,0 +1,435 @@
+// semantic analysis: type checking of a class against the signatures of the whole program
+// undefined variables are always errors (even with --type-check off): the code generator needs every variable
+// jack is weakly typed, so there are two kinds of problems:
+//   mismatch: surely a bug (e.g. a String assigned to an int, a value returned from a void function)
+//   doubtful: often an idiom (e.g. an int used as a condition, an int assigned to an object)
+
+use std::collections::HashMap;
+use crate::ast::{Class, Expression, Statement, SubroutineCall, SubroutineDec, Term};
+use crate::diagnostic::Diagnostic;
+use crate::lexical_analysis::{Keyword, Span};
+use crate::program_info::ProgramInfo;
+
+// how type problems are reported
+#[derive(Clone, Copy, PartialEq)]
+pub enum TypeCheckLevel {
+    // no type checking
+    Off,
+    // all problems are warnings
+    Warn,
+    // mismatches are errors, doubtful code is a warning
+    Error,
+    // all problems are errors
+    Strict,
+}
+
+#[derive(Clone, PartialEq)]
+enum Type {
+    Int,
+    Char,
+    Boolean,
+    Class(String),
+    Null,
+    Void,
+    // e.g. array elements and results of unknown subroutines
+    Unknown,
+}
+
+impl Type {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "int" => Type::Int,
+            "char" => Type::Char,
+            "boolean" => Type::Boolean,
+            "void" => Type::Void,
+            _ => Type::Class(name.to_string()),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Type::Int => "int".to_string(),
+            Type::Char => "char".to_string(),
+            Type::Boolean => "boolean".to_string(),
+            Type::Class(name) => name.to_string(),
+            Type::Null => "null".to_string(),
+            Type::Void => "void".to_string(),
+            Type::Unknown => "unknown".to_string(),
+        }
+    }
+
+    fn is_primitive(&self) -> bool {
+        return *self == Type::Int || *self == Type::Char || *self == Type::Boolean;
+    }
+}
+
+#[derive(PartialEq)]
+enum Compatibility {
+    Ok,
+    Doubtful,
+    Mismatch,
+}
+
+// can a value of type actual be stored into a variable of type expected?
+fn compatibility(expected: &Type, actual: &Type) -> Compatibility {
+    let array = Type::Class("Array".to_string());
+    if *expected == Type::Unknown || *actual == Type::Unknown || expected == actual {
+        return Compatibility::Ok;
+    } else if *actual == Type::Void || *expected == Type::Void {
+        return Compatibility::Mismatch;
+    } else if (*expected == Type::Int || *expected == Type::Char) && (*actual == Type::Int || *actual == Type::Char || *actual == array) {
+        return Compatibility::Ok;
+    } else if let Type::Class(_) = expected {
+        // Array is raw memory: any object or an address (int) is accepted, and it can be used as an address
+        if *actual == Type::Null || (*expected == array && !(*actual == Type::Boolean)) || (*actual == array) {
+            return Compatibility::Ok;
+        } else if let Type::Class(_) = actual {
+            return Compatibility::Mismatch;
+        } else {
+            return Compatibility::Doubtful;
+        }
+    } else if let Type::Class(_) = actual {
+        return Compatibility::Mismatch;
+    } else {
+        // boolean <-> int, char and null
+        return Compatibility::Doubtful;
+    }
+}
+
+// operand of an arithmetic operator
+fn numeric(actual: &Type) -> Compatibility {
+    match actual {
+        Type::Int | Type::Char | Type::Unknown => return Compatibility::Ok,
+        Type::Class(name) if name == "Array" => return Compatibility::Ok,
+        Type::Boolean | Type::Null => return Compatibility::Doubtful,
+        _ => return Compatibility::Mismatch,
+    }
+}
+
+struct Data<'a> {
+    class: &'a Class,
+    program_info: &'a ProgramInfo,
+    level: TypeCheckLevel,
+    class_vars: HashMap<String, Type>,
+    local_vars: HashMap<String, Type>,
+    subroutine: Option<&'a SubroutineDec>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub fn check_class(class: &Class, program_info: &ProgramInfo, level: TypeCheckLevel) -> Vec<Diagnostic> {
+    let mut data = Data {class, program_info, level, class_vars: HashMap::new(), local_vars: HashMap::new(), subroutine: None, diagnostics: vec![]};
+    for var_dec in &class.var_decs {
+        for (name, _) in &var_dec.names {
+            data.class_vars.insert(name.to_string(), Type::from_name(&var_dec.var_type));
+        }
+    }
+    for subroutine in &class.subroutines {
+        data.local_vars = HashMap::new();
+        for parameter in &subroutine.parameters {
+            data.local_vars.insert(parameter.name.to_string(), Type::from_name(&parameter.var_type));
+        }
+        for var_dec in &subroutine.var_decs {
+            for (name, _) in &var_dec.names {
+                data.local_vars.insert(name.to_string(), Type::from_name(&var_dec.var_type));
+            }
+        }
+        data.subroutine = Some(subroutine);
+        check_statements(&subroutine.statements, &mut data);
+    }
+    return data.diagnostics;
+}
+
+// ---------- statements ----------
+fn check_statements(statements: &Vec<Statement>, data: &mut Data) {
+    for statement in statements {
+        check_statement(statement, data);
+    }
+}
+
+fn check_statement(statement: &Statement, data: &mut Data) {
+    match statement {
+        Statement::Let {name, index, value, span} => {
+            let value_type = check_expression(value, data);
+            let var_type = match variable_type(name, data) {
+                Some(var_type) => var_type,
+                None => {
+                    undefined_variable(name, span, data);
+                    if let Some(index) = index {
+                        check_expression(index, data);
+                    }
+                    return;
+                }
+            };
+            if let Some(index) = index {
+                check_indexing(name, &var_type, index, span, data);
+            } else {
+                let compatibility = compatibility(&var_type, &value_type);
+                report(compatibility, &value.span, format!("{} is assigned to '{}' of type {}", value_type.name(), name, var_type.name()), data);
+            }
+        }
+        Statement::If {condition, then_statements, else_statements, ..} => {
+            check_condition(condition, data);
+            check_statements(then_statements, data);
+            if let Some(else_statements) = else_statements {
+                check_statements(else_statements, data);
+            }
+        }
+        Statement::While {condition, statements, ..} => {
+            check_condition(condition, data);
+            check_statements(statements, data);
+        }
+        Statement::For {init, condition, update, statements, ..} => {
+            if let Some(init) = init {
+                check_statement(init, data);
+            }
+            if let Some(condition) = condition {
+                check_condition(condition, data);
+            }
+            if let Some(update) = update {
+                check_statement(update, data);
+            }
+            check_statements(statements, data);
+        }
+        Statement::Break {..} | Statement::Continue {..} => {}
+        Statement::Do {call, ..} => {
+            check_subroutine_call(call, data);
+        }
+        Statement::Return {value, span} => {
+            let subroutine = data.subroutine.expect("program error: return statement out of subroutine");
+            let return_type = Type::from_name(&subroutine.return_type);
+            if let Some(value) = value {
+                let value_type = check_expression(value, data);
+                if return_type == Type::Void {
+                    report(Compatibility::Mismatch, span, format!("void {} '{}' returns a value", subroutine.kind.name(), subroutine.name), data);
+                } else {
+                    let compatibility = compatibility(&return_type, &value_type);
+                    report(compatibility, &value.span, format!("{} is returned from '{}' of type {}", value_type.name(), subroutine.name, return_type.name()), data);
+                }
+            }
+        }
+    }
+}
+
+fn check_condition(condition: &Expression, data: &mut Data) {
+    let condition_type = check_expression(condition, data);
+    if condition_type != Type::Boolean && condition_type != Type::Unknown {
+        let compatibility = if condition_type == Type::Void {Compatibility::Mismatch} else {Compatibility::Doubtful};
+        report(compatibility, &condition.span, format!("condition is {}, not boolean", condition_type.name()), data);
+    }
+}
+
+// name[index]: name should be an Array
+fn check_indexing(name: &str, var_type: &Type, index: &Expression, span: &Span, data: &mut Data) {
+    if var_type.is_primitive() {
+        report(Compatibility::Mismatch, span, format!("'{}' of type {} is not an array", name, var_type.name()), data);
+    } else if *var_type != Type::Class("Array".to_string()) {
+        report(Compatibility::Doubtful, span, format!("'{}' of type {} is used as an array", name, var_type.name()), data);
+    }
+    let index_type = check_expression(index, data);
+    report(numeric(&index_type), &index.span, format!("array index is {}, not int", index_type.name()), data);
+}
+
+// ---------- expression ----------
+fn check_expression(expression: &Expression, data: &mut Data) -> Type {
+    let mut left = check_term(&expression.term, data);
+    let mut left_span = expression.term.span().clone();
+    for (operator, term) in &expression.operations {
+        let right = check_term(term, data);
+        let right_span = term.span().clone();
+        left = match operator {
+            '+' | '-' | '*' | '/' => {
+                // the result of a wrong operation is unknown, so that the error is not reported again
+                let valid = numeric(&left) != Compatibility::Mismatch && numeric(&right) != Compatibility::Mismatch;
+                report(numeric(&left), &left_span, format!("left operand of '{}' is {}", operator, left.name()), data);
+                report(numeric(&right), &right_span, format!("right operand of '{}' is {}", operator, right.name()), data);
+                if valid {Type::Int} else {Type::Unknown}
+            }
+            '<' | '>' => {
+                report(numeric(&left), &left_span, format!("left operand of '{}' is {}", operator, left.name()), data);
+                report(numeric(&right), &right_span, format!("right operand of '{}' is {}", operator, right.name()), data);
+                Type::Boolean
+            }
+            '&' | '|' => {
+                // boolean operators, or bitwise operators of int
+                if left == Type::Boolean || right == Type::Boolean {
+                    report(logical(&left), &left_span, format!("left operand of '{}' is {}", operator, left.name()), data);
+                    report(logical(&right), &right_span, format!("right operand of '{}' is {}", operator, right.name()), data);
+                    Type::Boolean
+                } else {
+                    report(numeric(&left), &left_span, format!("left operand of '{}' is {}", operator, left.name()), data);
+                    report(numeric(&right), &right_span, format!("right operand of '{}' is {}", operator, right.name()), data);
+                    if left == Type::Unknown || right == Type::Unknown {Type::Unknown} else {Type::Int}
+                }
+            }
+            _ => {
+                // '='
+                let compatibility = match (compatibility(&left, &right), compatibility(&right, &left)) {
+                    (Compatibility::Ok, _) | (_, Compatibility::Ok) => Compatibility::Ok,
+                    (Compatibility::Doubtful, _) | (_, Compatibility::Doubtful) => Compatibility::Doubtful,
+                    _ => Compatibility::Mismatch,
+                };
+                report(compatibility, &right_span, format!("{} is compared with {}", left.name(), right.name()), data);
+                Type::Boolean
+            }
+        };
+        left_span = expression.span.clone();
+    }
+    return left;
+}
+
+// operand of '&' or '|' when the other operand is boolean
+fn logical(actual: &Type) -> Compatibility {
+    match actual {
+        Type::Boolean | Type::Unknown => return Compatibility::Ok,
+        Type::Int | Type::Char => return Compatibility::Doubtful,
+        _ => return Compatibility::Mismatch,
+    }
+}
+
+fn check_term(term: &Term, data: &mut Data) -> Type {
+    match term {
+        Term::IntConst(_, _) => return Type::Int,
+        Term::StringConst(_, _) => return Type::Class("String".to_string()),
+        Term::KeywordConst(keyword, _) => {
+            if *keyword == Keyword::True || *keyword == Keyword::False {
+                return Type::Boolean;
+            } else if *keyword == Keyword::Null {
+                return Type::Null;
+            } else {
+                return Type::Class(data.class.name.to_string());
+            }
+        }
+        Term::Var(name, span) => {
+            if let Some(var_type) = variable_type(name, data) {
+                return var_type;
+            }
+            undefined_variable(name, span, data);
+            return Type::Unknown;
+        }
+        Term::ArrayElement(name, index, span) => {
+            if let Some(var_type) = variable_type(name, data) {
+                check_indexing(name, &var_type, index, span, data);
+            } else {
+                undefined_variable(name, span, data);
+                check_expression(index, data);
+            }
+            return Type::Unknown;
+        }
+        Term::Parenthesized(expression, _) => {
+            return check_expression(expression, data);
+        }
+        Term::Unary(operator, term, span) => {
+            let term_type = check_term(term, data);
+            if *operator == '~' && term_type == Type::Boolean {
+                return Type::Boolean;
+            }
+            report(numeric(&term_type), span, format!("operand of '{}' is {}", operator, term_type.name()), data);
+            return Type::Int;
+        }
+        Term::Call(call) => {
+            return check_subroutine_call(call, data);
+        }
+    }
+}
+
+// arguments of a known subroutine are checked: returns the return type
+fn check_subroutine_call(call: &SubroutineCall, data: &mut Data) -> Type {
+    let mut argument_types = vec![];
+    for argument in &call.arguments {
+        argument_types.push(check_expression(argument, data));
+    }
+
+    // the class of the subroutine: the current class, the type of a variable or a class name
+    let class_name = match &call.receiver {
+        None => data.class.name.to_string(),
+        Some(receiver) => match variable_type(receiver, data) {
+            Some(Type::Class(class_name)) => class_name,
+            // a method call on int, char or boolean is reported by the resolver
+            Some(_) => return Type::Unknown,
+            None if data.program_info.is_class_name(receiver) => receiver.to_string(),
+            None => {
+                undefined_variable(receiver, &call.span, data);
+                return Type::Unknown;
+            }
+        },
+    };
+    let (return_type, parameter_types) = match data.program_info.find(&class_name, &call.name) {
+        Some(info) => (Type::from_name(&info.return_type), info.parameter_types.clone()),
+        None => return Type::Unknown,
+    };
+
+    // the number of arguments is checked by the resolver
+    if parameter_types.len() == argument_types.len() {
+        for (i, (parameter_type, argument_type)) in parameter_types.iter().zip(argument_types.iter()).enumerate() {
+            let compatibility = compatibility(&Type::from_name(parameter_type), argument_type);
+            report(compatibility, &call.arguments[i].span, format!("argument {} of '{}.{}' is {}, not {}", i + 1, class_name, call.name, argument_type.name(), parameter_type), data);
+        }
+    }
+    return return_type;
+}
+
+// subroutine
+fn variable_type(name: &str, data: &Data) -> Option<Type> {
+    if let Some(var_type) = data.local_vars.get(name) {
+        return Some(var_type.clone());
+    } else if let Some(var_type) = data.class_vars.get(name) {
+        return Some(var_type.clone());
+    } else {
+        return None;
+    }
+}
+
+fn undefined_variable(name: &str, span: &Span, data: &mut Data) {
+    let diagnostic = Diagnostic::error(span, format!("variable '{}' is not defined", name));
+    data.diagnostics.push(diagnostic.with_hint("declare it with 'var' (or 'static', 'field')"));
+}
+
+fn report(compatibility: Compatibility, span: &Span, message: String, data: &mut Data) {
+    let is_error = match compatibility {
+        _ if data.level == TypeCheckLevel::Off => return,
+        Compatibility::Ok => return,
+        Compatibility::Mismatch => data.level != TypeCheckLevel::Warn,
+        Compatibility::Doubtful => data.level == TypeCheckLevel::Strict,
+    };
+    if is_error {
+        data.diagnostics.push(Diagnostic::error(span, format!("type error: {}", message)));
+    } else {
+        data.diagnostics.push(Diagnostic::warning(span, format!("type warning: {}", message)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexical_analysis::Lexicon;
+    use crate::parser::parse_class;
+
+    // errors of the type check of "class Main { body }" (the only class with the Jack OS)
+    fn check_errors(body: &str, level: TypeCheckLevel) -> Vec<String> {
+        let source = format!("class Main {{\n{}\n}}\n", body);
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", &source, false);
+        let (class, syntax_errors) = parse_class(&lexicons, "Main.jack", false, false);
+        assert!(lexical_errors.is_empty() && syntax_errors.is_empty(), "invalid test code");
+        let mut program_info = ProgramInfo::new();
+        program_info.add_os_api();
+        program_info.add_class(&class);
+        let diagnostics = check_class(&class, &program_info, level);
+        return diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).map(|diagnostic| format!("{} {}", diagnostic.span.line, diagnostic.message)).collect();
+    }
+
+    #[test]
+    fn undefined_variables_are_errors_with_type_check_off() {
+        let messages = check_errors("function void f() {
+            let x = 1;
+            let a[0] = y;
+            do f.g();
+            return;
+        }", TypeCheckLevel::Off);
+        assert_eq!(messages, vec![
+            "3 variable 'x' is not defined",
+            "4 variable 'y' is not defined",
+            "4 variable 'a' is not defined",
+            "5 variable 'f' is not defined",
+        ]);
+    }
+}
@@ -0,0 +1,318 @@
+// usage analysis of variables: warnings for
+//   local variables never read, or read before any 'let' assigns them (the vm initializes them to 0)
+//   parameters never read, and fields never used in any subroutine
+// a declaration with "@unused" in a comment on the same line is not reported, e.g. "var int x;  // @unused"
+
+use std::collections::{HashMap, HashSet};
+use crate::ast::{Class, ClassVarKind, Expression, Statement, SubroutineCall, Term};
+use crate::control_flow::terminates;
+use crate::diagnostic::Diagnostic;
+use crate::lexical_analysis::Span;
+
+struct Variable {
+    span: Span,
+    is_parameter: bool,
+    read: bool,
+    written: bool,
+}
+
+struct Data<'a> {
+    source: &'a str,
+    locals: HashMap<String, Variable>,
+    // fields used in any subroutine
+    used_fields: HashSet<String>,
+    // local variables already reported as read before assignment
+    reported: HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+// source: the whole jack code of the class (for "@unused" comments)
+pub fn check_usage(class: &Class, source: &str) -> Vec<Diagnostic> {
+    let mut data = Data {source, locals: HashMap::new(), used_fields: HashSet::new(), reported: HashSet::new(), diagnostics: vec![]};
+    for subroutine in &class.subroutines {
+        data.locals = HashMap::new();
+        data.reported = HashSet::new();
+        for parameter in &subroutine.parameters {
+            data.locals.insert(parameter.name.to_string(), Variable {span: parameter.span.clone(), is_parameter: true, read: false, written: true});
+        }
+        for var_dec in &subroutine.var_decs {
+            for (name, span) in &var_dec.names {
+                data.locals.insert(name.to_string(), Variable {span: span.clone(), is_parameter: false, read: false, written: false});
+            }
+        }
+        // parameters are assigned by the caller
+        let assigned = subroutine.parameters.iter().map(|parameter| parameter.name.to_string()).collect();
+        check_statements(&subroutine.statements, assigned, &mut data);
+
+        let mut locals: Vec<(&String, &Variable)> = data.locals.iter().collect();
+        locals.sort_by_key(|(_, variable)| variable.span.start);
+        let mut warnings = vec![];
+        for (name, variable) in locals {
+            if variable.read {
+                continue;
+            } else if variable.is_parameter {
+                warnings.push((variable.span.clone(), format!("parameter '{}' of '{}' is never used", name, subroutine.name)));
+            } else if variable.written {
+                warnings.push((variable.span.clone(), format!("local variable '{}' is assigned but never read", name)));
+            } else {
+                warnings.push((variable.span.clone(), format!("local variable '{}' is never used", name)));
+            }
+        }
+        for (span, message) in warnings {
+            warn_unless_allowed(&span, message, &mut data);
+        }
+    }
+
+    for var_dec in &class.var_decs {
+        if var_dec.kind != ClassVarKind::Field {
+            continue;
+        }
+        for (name, span) in &var_dec.names {
+            if !data.used_fields.contains(name) {
+                warn_unless_allowed(span, format!("field '{}' is never used", name), &mut data);
+            }
+        }
+    }
+    return data.diagnostics;
+}
+
+fn warn_unless_allowed(span: &Span, message: String, data: &mut Data) {
+    let line = data.source.lines().nth(span.line - 1).unwrap_or("");
+    if let Some(comment) = comment_start(line) {
+        if line[comment..].contains("@unused") {
+            return;
+        }
+    }
+    data.diagnostics.push(Diagnostic::warning(span, message).with_hint("add a comment \"// @unused\" to the declaration to keep it"));
+}
+
+// byte index of "//" or "/*" in the line (not in a string constant)
+fn comment_start(line: &str) -> Option<usize> {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if line[i..].starts_with("//") || line[i..].starts_with("/*") {
+            return Some(i);
+        }
+    }
+    return None;
+}
+
+// ---------- statements ----------
+// assigned: local variables assigned on every path so far: returns them after the statements
+fn check_statements(statements: &Vec<Statement>, mut assigned: HashSet<String>, data: &mut Data) -> HashSet<String> {
+    for statement in statements {
+        assigned = check_statement(statement, assigned, data);
+    }
+    return assigned;
+}
+
+fn check_statement(statement: &Statement, mut assigned: HashSet<String>, data: &mut Data) -> HashSet<String> {
+    match statement {
+        Statement::Let {name, index, value, span} => {
+            if let Some(index) = index {
+                // name[index] = value: the array itself is read
+                read_variable(name, span, &assigned, data);
+                check_expression(index, &assigned, data);
+                check_expression(value, &assigned, data);
+            } else {
+                check_expression(value, &assigned, data);
+                if let Some(variable) = data.locals.get_mut(name) {
+                    variable.written = true;
+                    assigned.insert(name.to_string());
+                } else {
+                    data.used_fields.insert(name.to_string());
+                }
+            }
+        }
+        Statement::If {condition, then_statements, else_statements, ..} => {
+            check_expression(condition, &assigned, data);
+            let then_assigned = check_statements(then_statements, assigned.clone(), data);
+            let else_assigned = match else_statements {
+                Some(else_statements) => check_statements(else_statements, assigned.clone(), data),
+                None => assigned.clone(),
+            };
+            // a branch which returns (or breaks, continues) on every path doesn't reach the next statement
+            if terminates(then_statements) {
+                assigned = else_assigned;
+            } else if else_statements.as_ref().is_some_and(|else_statements| terminates(else_statements)) {
+                assigned = then_assigned;
+            } else {
+                assigned = then_assigned.intersection(&else_assigned).cloned().collect();
+            }
+        }
+        Statement::While {condition, statements, ..} => {
+            // the body may not be executed
+            check_expression(condition, &assigned, data);
+            check_statements(statements, assigned.clone(), data);
+        }
+        Statement::Do {call, ..} => {
+            check_subroutine_call(call, &assigned, data);
+        }
+        Statement::Return {value, ..} => {
+            if let Some(value) = value {
+                check_expression(value, &assigned, data);
+            }
+        }
+        Statement::For {init, condition, update, statements, ..} => {
+            if let Some(init) = init {
+                assigned = check_statement(init, assigned, data);
+            }
+            if let Some(condition) = condition {
+                check_expression(condition, &assigned, data);
+            }
+            // the body may not be executed: the update follows it
+            let body_assigned = check_statements(statements, assigned.clone(), data);
+            if let Some(update) = update {
+                check_statement(update, body_assigned, data);
+            }
+        }
+        Statement::Break {..} | Statement::Continue {..} => {}
+    }
+    return assigned;
+}
+
+// ---------- expression ----------
+fn check_expression(expression: &Expression, assigned: &HashSet<String>, data: &mut Data) {
+    check_term(&expression.term, assigned, data);
+    for (_, term) in &expression.operations {
+        check_term(term, assigned, data);
+    }
+}
+
+fn check_term(term: &Term, assigned: &HashSet<String>, data: &mut Data) {
+    match term {
+        Term::Var(name, span) => read_variable(name, span, assigned, data),
+        Term::ArrayElement(name, index, span) => {
+            read_variable(name, span, assigned, data);
+            check_expression(index, assigned, data);
+        }
+        Term::Parenthesized(expression, _) => check_expression(expression, assigned, data),
+        Term::Unary(_, term, _) => check_term(term, assigned, data),
+        Term::Call(call) => check_subroutine_call(call, assigned, data),
+        _ => {}
+    }
+}
+
+fn check_subroutine_call(call: &SubroutineCall, assigned: &HashSet<String>, data: &mut Data) {
+    if let Some(receiver) = &call.receiver {
+        read_variable(receiver, &call.span, assigned, data);
+    }
+    for argument in &call.arguments {
+        check_expression(argument, assigned, data);
+    }
+}
+
+// subroutine
+// name is a local variable, a field (or a static, or a class name)
+fn read_variable(name: &str, span: &Span, assigned: &HashSet<String>, data: &mut Data) {
+    let variable = match data.locals.get_mut(name) {
+        Some(variable) => variable,
+        None => {
+            data.used_fields.insert(name.to_string());
+            return;
+        }
+    };
+    variable.read = true;
+    if !assigned.contains(name) && data.reported.insert(name.to_string()) {
+        let message = format!("local variable '{}' is read before it is assigned", name);
+        data.diagnostics.push(Diagnostic::warning(span, message).with_hint("its value is 0"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexical_analysis::Lexicon;
+    use crate::parser::parse_class;
+
+    // warnings of the usage analysis of "class Main { body }"
+    fn check(body: &str) -> Vec<String> {
+        let source = format!("class Main {{\n{}\n}}\n", body);
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", &source, true);
+        let (class, syntax_errors) = parse_class(&lexicons, "Main.jack", true, false);
+        assert!(lexical_errors.is_empty() && syntax_errors.is_empty(), "invalid test code");
+        return check_usage(&class, &source).iter().map(|diagnostic| format!("{} {}", diagnostic.span.line, diagnostic.message)).collect();
+    }
+
+    #[test]
+    fn assigned_in_both_branches() {
+        let messages = check("function int f(int a) {
+            var int x;
+            if (a > 0) { let x = 1; } else { let x = 2; }
+            return x;
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn assigned_in_one_branch() {
+        let messages = check("function int f(int a) {
+            var int x;
+            if (a > 0) { let x = 1; }
+            return x;
+        }");
+        assert_eq!(messages, vec!["5 local variable 'x' is read before it is assigned"]);
+    }
+
+    #[test]
+    fn branch_returning_in_a_nested_if_else() {
+        let messages = check("function int f(int a) {
+            var int x;
+            if (a > 0) {
+                if (a > 1) { return 1; } else { return 2; }
+            } else {
+                let x = 1;
+            }
+            return x;
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn assigned_in_for_init() {
+        let messages = check("function int f() {
+            var int i, sum;
+            let sum = 0;
+            for (let i = 0; i < 10; let i = i + 1) {
+                let sum = sum + i;
+            }
+            return sum + i;
+        }");
+        assert!(messages.is_empty(), "{:?}", messages);
+    }
+
+    #[test]
+    fn assigned_only_in_for_body() {
+        let messages = check("function int f(int n) {
+            var int i, last;
+            for (let i = 0; i < n; let i = i + 1) {
+                let last = i;
+            }
+            return last;
+        }");
+        assert_eq!(messages, vec!["7 local variable 'last' is read before it is assigned"]);
+    }
+
+    #[test]
+    fn unused_opt_out() {
+        let messages = check("field int kept;  // @unused
+            field int dropped;
+            function void f(int a) {  /* @unused */
+                var int x, y;  // @unused
+                var String s; let s = \"// @unused\";
+                return;
+            }");
+        assert_eq!(messages, vec!["6 local variable 's' is assigned but never read", "3 field 'dropped' is never used"]);
+    }
+}
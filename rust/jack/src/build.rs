@@ -0,0 +1,204 @@
+// jack build: compiling a project directory into out_dir/Foo.vm, out_dir/project.asm and out_dir/project.hack
+// the last build is recorded in out_dir/jack-build.manifest (its options and the signatures of all classes)
+// files in out_dir which are not generated by the tools are never overwritten
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use hackassembler::assemble;
+use jack_frontend::diagnostic::report;
+use tool_output::{Marker, OutDir};
+use jack_frontend::type_checker::TypeCheckLevel;
+use jackcompiler::analyze;
+use jackcompiler::codegen::compile_class;
+use vmtranslator::backend_asm::{vmfiles_to_asm, Options};
+use vmtranslator::call_graph::CallGraph;
+use vmtranslator::{vmfile_paths, vmlint};
+
+pub struct BuildOptions {
+    pub out_dir: Option<PathBuf>,
+    pub os_dir: Option<PathBuf>,
+    pub extensions: bool,
+    pub precedence: bool,
+    pub type_check: TypeCheckLevel,
+    pub debug_info: bool,
+}
+
+// returns false if there are errors
+pub fn build(project: &Path, options: &BuildOptions) -> bool {
+    let out_dir = match &options.out_dir {
+        Some(out_dir) => out_dir.clone(),
+        None => project.join("build"),
+    };
+    if let Err(why) = fs::create_dir_all(&out_dir) {
+        panic!("couldn't create {}: {}", out_dir.display(), why);
+    }
+    let project_name = match project.canonicalize().ok().and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string())) {
+        Some(name) => name,
+        None => panic!("error: invalid path {}", project.display()),
+    };
+    let jack_paths = jackfile_paths(project);
+    if jack_paths.is_empty() {
+        panic!("error: no .jack file in {}", project.display());
+    }
+    let vm_paths: Vec<PathBuf> = jack_paths.iter().map(|path| out_path(&out_dir, path, "vm")).collect();
+    let hack_path = out_dir.join(format!("{}.hack", project_name));
+    let manifest_path = out_dir.join("jack-build.manifest");
+    let old_manifest = fs::read_to_string(&manifest_path).unwrap_or_default();
+    let options_line = options_to_string(options);
+
+    // incremental build: nothing to do when every output is newer than its inputs and the options are the same
+    let same_options = old_manifest.lines().next() == Some(options_line.as_str());
+    if same_options && is_up_to_date(&jack_paths, &vm_paths, &hack_path, &old_manifest, options) {
+        println!("{}: up to date", hack_path.display());
+        return true;
+    }
+
+    // compiling: all classes are analyzed (their signatures are needed to check the others),
+    // but only changed .jack files are compiled again unless some signature is changed
+    let (mut units, program_info) = analyze(&jack_paths, true, options.extensions, options.precedence, options.type_check);
+    let mut success = true;
+    for unit in &mut units {
+        success = report(&mut unit.diagnostics, &unit.source) && success;
+    }
+    if !success {
+        return false;
+    }
+    let mut manifest = options_line + "\n";
+    for unit in &units {
+        manifest += &format!("class {}\n", unit.class.name);
+        for subroutine in &unit.class.subroutines {
+            let parameter_types: Vec<&str> = subroutine.parameters.iter().map(|parameter| parameter.var_type.as_str()).collect();
+            let signature = format!("subroutine {} {} {} {}", subroutine.kind.name(), subroutine.return_type, subroutine.name, parameter_types.join(" "));
+            manifest += &format!("{}\n", signature.trim_end());
+        }
+    }
+    let compile_all = manifest != old_manifest;
+    let mut compiled = 0;
+    for (unit, vm_path) in units.iter().zip(&vm_paths) {
+        if compile_all || !is_newer(vm_path, &unit.path) {
+            let contents = compile_class(&unit.class, &unit.filename, &program_info);
+            OutDir::Directory(out_dir.clone()).write(vm_path, &format!("{}\n", contents), "jack build", Marker::Comment("//"));
+            compiled += 1;
+        }
+    }
+
+    // translating all .vm files (and the Jack OS) into one asm program: dead functions are dropped
+    let lib_dirs: Vec<PathBuf> = options.os_dir.iter().cloned().collect();
+    let (vm_files, mut diagnostics) = vmlint(&vm_paths, &lib_dirs, &[]);
+    if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+        vmtranslator::report(&diagnostics, false);
+        if options.os_dir.is_none() {
+            eprintln!("hint: link the Jack OS with --os path/to/os");
+        }
+        return false;
+    }
+    let call_graph = CallGraph::build(&vm_files);
+    let live_functions = if call_graph.contains("Sys.init") {Some(call_graph.reachable_from("Sys.init"))} else {None};
+    let asm_options = Options {debug_info: options.debug_info, checked: false};
+    let asm_string = vmfiles_to_asm(&vm_files, &live_functions, &asm_options, &mut diagnostics);
+    if !vmtranslator::report(&diagnostics, false) {
+        return false;
+    }
+    let asm_path = out_dir.join(format!("{}.asm", project_name));
+    OutDir::Directory(out_dir.clone()).write(&asm_path, &format!("{}\n", asm_string), "jack build", Marker::Comment("//"));
+
+    // assembling: project.hack, project.statics (and project.srcmap with --debug-info)
+    let asmfile = match File::open(&asm_path) {
+        Err(why) => panic!("couldn't open {}: {}", asm_path.display(), why),
+        Ok(file) => file,
+    };
+    let (binary_str, static_map_str, source_map_str) = assemble(&asmfile);
+    let hack_out_dir = OutDir::Directory(out_dir.clone());
+    hack_out_dir.write(&out_dir.join(format!("{}.statics", project_name)), &static_map_str, "jack build", Marker::Comment("//"));
+    if let Some(source_map_str) = source_map_str {
+        hack_out_dir.write(&out_dir.join(format!("{}.srcmap", project_name)), &source_map_str, "jack build", Marker::Comment("//"));
+    }
+    hack_out_dir.write(&hack_path, &format!("{}\n", binary_str), "jack build", Marker::NoComment);
+
+    // the manifest is written last: an interrupted build is done again
+    write_file(&manifest_path, &manifest);
+    println!("{}: {} of {} class(es) compiled", hack_path.display(), compiled, units.len());
+    return true;
+}
+
+// .jack files in the directory (sorted by path)
+fn jackfile_paths(project: &Path) -> Vec<PathBuf> {
+    let directory = match project.read_dir() {
+        Err(why) => panic!("couldn't open directory {}: {}", project.display(), why),
+        Ok(dir) => dir,
+    };
+    let mut paths = vec![];
+    for dir_entry in directory {
+        if let Ok(entry) = dir_entry {
+            let path_of_entry = entry.path();
+            if path_of_entry.extension().is_some_and(|extension| extension == "jack") {
+                paths.push(path_of_entry);
+            }
+        } else {
+            eprintln!("warning: couldn't access to some entry in {}", project.display());
+        }
+    }
+    paths.sort();
+    return paths;
+}
+
+// path/to/Foo.jack -> out_dir/Foo.extension
+fn out_path(out_dir: &Path, path: &Path, extension: &str) -> PathBuf {
+    let mut new_path = out_dir.join(path.file_name().expect("error: invalid filename"));
+    new_path.set_extension(extension);
+    return new_path;
+}
+
+fn options_to_string(options: &BuildOptions) -> String {
+    let type_check = match options.type_check {
+        TypeCheckLevel::Off => "off",
+        TypeCheckLevel::Warn => "warn",
+        TypeCheckLevel::Error => "error",
+        TypeCheckLevel::Strict => "strict",
+    };
+    let os_dir = options.os_dir.as_ref().map_or("".to_string(), |os_dir| os_dir.display().to_string());
+    return format!("options extensions={} precedence={} type-check={} debug-info={} os={}", options.extensions, options.precedence, type_check, options.debug_info, os_dir);
+}
+
+// the classes are the same as the last build, and foo.vm and project.hack are newer than their inputs
+fn is_up_to_date(jack_paths: &[PathBuf], vm_paths: &[PathBuf], hack_path: &Path, manifest: &str, options: &BuildOptions) -> bool {
+    let classes: Vec<&str> = manifest.lines().filter_map(|line| line.strip_prefix("class ")).collect();
+    let stems: Vec<String> = jack_paths.iter().map(|path| path.file_stem().unwrap_or_default().to_string_lossy().to_string()).collect();
+    if classes != stems {
+        return false;
+    }
+    for (jack_path, vm_path) in jack_paths.iter().zip(vm_paths) {
+        if !is_newer(vm_path, jack_path) || !is_newer(hack_path, vm_path) {
+            return false;
+        }
+    }
+    if let Some(os_dir) = &options.os_dir {
+        if vmfile_paths(os_dir).iter().any(|os_path| !is_newer(hack_path, os_path)) {
+            return false;
+        }
+    }
+    return true;
+}
+
+// output exists and is not older than input
+fn is_newer(output: &Path, input: &Path) -> bool {
+    return match (modified(output), modified(input)) {
+        (Some(output_time), Some(input_time)) => output_time >= input_time,
+        _ => false,
+    };
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    return fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+}
+
+fn write_file(path: &Path, contents: &str) {
+    let mut file = match File::create(path) {
+        Err(why) => panic!("couldn't create {}: {}", path.display(), why),
+        Ok(file) => file,
+    };
+    write!(file, "{}", contents).expect("couldn't write to file");
+}
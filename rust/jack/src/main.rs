@@ -0,0 +1,69 @@
+// driver of the jack tools: jack -> vm -> asm -> hack in one command
+// コンピュータシステムの理論と実装 §6, §7, §8, §10, §11
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use jack_frontend::type_checker::TypeCheckLevel;
+
+mod build;
+use crate::build::{build, BuildOptions};
+
+fn main() {
+    // jack build [options] path/to/project: all .jack files in the directory are one program
+    //   -o dir, --out-dir dir: writes Foo.vm, project.asm and project.hack into dir (default: path/to/project/build)
+    //   --os dir: links Foo.vm of the Jack OS in dir when Foo.bar is called (like -L of vmtranslator)
+    //   --extensions, --precedence, --type-check off|warn|error|strict: the same as jackcompiler
+    //   --debug-info: annotates asm code with the .vm file and line, and writes project.srcmap
+    // only changed .jack files are compiled again, and nothing is done when the outputs are up to date
+    let usage = "usage: ./jack build [-o out_dir] [--os dir] [--extensions] [--precedence] [--type-check off|warn|error|strict] [--debug-info] path/to/dir";
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 || args[1] != "build" {
+        panic!("{}", usage);
+    }
+    let mut options = BuildOptions {out_dir: None, os_dir: None, extensions: false, precedence: false, type_check: TypeCheckLevel::Error, debug_info: false};
+    let mut input_path = None;
+    let mut args_iter = args.iter().skip(2);
+    while let Some(arg) = args_iter.next() {
+        if arg == "-o" || arg == "--out-dir" {
+            match args_iter.next() {
+                Some(out_dir) => options.out_dir = Some(PathBuf::from(out_dir)),
+                None => panic!("error: {} needs an output directory", arg),
+            }
+        } else if arg == "--os" {
+            match args_iter.next() {
+                Some(os_dir) => options.os_dir = Some(PathBuf::from(os_dir)),
+                None => panic!("error: --os needs a directory of the Jack OS .vm files"),
+            }
+        } else if arg == "--extensions" {
+            options.extensions = true;
+        } else if arg == "--precedence" {
+            options.precedence = true;
+        } else if arg == "--type-check" {
+            options.type_check = match args_iter.next().map(|level| level.as_str()) {
+                Some("off") => TypeCheckLevel::Off,
+                Some("warn") => TypeCheckLevel::Warn,
+                Some("error") => TypeCheckLevel::Error,
+                Some("strict") => TypeCheckLevel::Strict,
+                _ => panic!("error: --type-check needs off, warn, error or strict"),
+            };
+        } else if arg == "--debug-info" {
+            options.debug_info = true;
+        } else {
+            input_path = Some(PathBuf::from(arg));
+        }
+    }
+    let path = match input_path {
+        Some(path) if path.is_dir() => path,
+        Some(path) => panic!("error: invalid path {}: input the directory of the project", path.display()),
+        None => panic!("{}", usage),
+    };
+
+    if !build(&path, &options) {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,271 @@
+// the parse tree of jack code as xml (the format of the test files of §10)
+// built from the ast of jack-frontend: every token is written back in order
+// コンピュータシステムの理論と実装 §10
+
+use jack_frontend::ast::{Class, ClassVarDec, Expression, Statement, SubroutineCall, SubroutineDec, Term, VarDec};
+use jack_frontend::lexical_analysis::{Keyword, Token};
+
+pub fn class_to_xml(class: &Class) -> String {
+    let indent = "\t";
+    let mut xml = "<class>\n".to_string();
+    xml += &keyword(Keyword::Class, indent);
+    xml += &identifier(&class.name, indent);
+    xml += &symbol('{', indent);
+    for var_dec in &class.var_decs {
+        xml += &class_var_dec_to_xml(var_dec, indent);
+    }
+    for subroutine in &class.subroutines {
+        xml += &subroutine_dec_to_xml(subroutine, indent);
+    }
+    xml += &symbol('}', indent);
+    xml += "</class>";
+    return xml;
+}
+
+// ---------- program structure ----------
+fn class_var_dec_to_xml(var_dec: &ClassVarDec, indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    let mut contents = match Keyword::from_word(var_dec.kind.name(), false) {
+        Some(kind) => keyword(kind, &new_indent),
+        None => unreachable!("static and field are keywords"),
+    };
+    contents += &var_type(&var_dec.var_type, &new_indent);
+    contents += &names(var_dec.names.iter().map(|(name, _)| name.as_str()), &new_indent);
+    contents += &symbol(';', &new_indent);
+    return element("classVarDec", &contents, indent);
+}
+
+fn subroutine_dec_to_xml(subroutine: &SubroutineDec, indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    let mut contents = match Keyword::from_word(subroutine.kind.name(), false) {
+        Some(kind) => keyword(kind, &new_indent),
+        None => unreachable!("constructor, function and method are keywords"),
+    };
+    contents += &var_type(&subroutine.return_type, &new_indent);
+    contents += &identifier(&subroutine.name, &new_indent);
+    contents += &symbol('(', &new_indent);
+
+    // parameterList :: ((type varName) ("," type varName)*)?
+    let mut parameters = "".to_string();
+    for (i, parameter) in subroutine.parameters.iter().enumerate() {
+        if i != 0 {
+            parameters += &symbol(',', &format!("{}\t", new_indent));
+        }
+        parameters += &var_type(&parameter.var_type, &format!("{}\t", new_indent));
+        parameters += &identifier(&parameter.name, &format!("{}\t", new_indent));
+    }
+    contents += &element("parameterList", &parameters, &new_indent);
+    contents += &symbol(')', &new_indent);
+
+    // subroutineBody :: "{" varDec* statements "}"
+    let body_indent = format!("{}\t", new_indent);
+    let mut body = symbol('{', &body_indent);
+    for var_dec in &subroutine.var_decs {
+        body += &var_dec_to_xml(var_dec, &body_indent);
+    }
+    body += &statements_to_xml(&subroutine.statements, &body_indent);
+    body += &symbol('}', &body_indent);
+    contents += &element("subroutineBody", &body, &new_indent);
+    return element("subroutineDec", &contents, indent);
+}
+
+fn var_dec_to_xml(var_dec: &VarDec, indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    let mut contents = keyword(Keyword::Var, &new_indent);
+    contents += &var_type(&var_dec.var_type, &new_indent);
+    contents += &names(var_dec.names.iter().map(|(name, _)| name.as_str()), &new_indent);
+    contents += &symbol(';', &new_indent);
+    return element("varDec", &contents, indent);
+}
+
+// varName ("," varName)*
+fn names<'a>(names: impl Iterator<Item = &'a str>, indent: &str) -> String {
+    let mut xml = "".to_string();
+    for (i, name) in names.enumerate() {
+        if i != 0 {
+            xml += &symbol(',', indent);
+        }
+        xml += &identifier(name, indent);
+    }
+    return xml;
+}
+
+// ---------- statements ----------
+fn statements_to_xml(statements: &[Statement], indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    let mut contents = "".to_string();
+    for statement in statements {
+        contents += &statement_to_xml(statement, &new_indent);
+    }
+    return element("statements", &contents, indent);
+}
+
+fn statement_to_xml(statement: &Statement, indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    match statement {
+        Statement::Let {name, index, value, ..} => {
+            let mut contents = keyword(Keyword::Let, &new_indent);
+            contents += &identifier(name, &new_indent);
+            if let Some(index) = index {
+                contents += &symbol('[', &new_indent);
+                contents += &expression_to_xml(index, &new_indent);
+                contents += &symbol(']', &new_indent);
+            }
+            contents += &symbol('=', &new_indent);
+            contents += &expression_to_xml(value, &new_indent);
+            contents += &symbol(';', &new_indent);
+            return element("letStatement", &contents, indent);
+        }
+        Statement::If {condition, then_statements, else_statements, ..} => {
+            let mut contents = keyword(Keyword::If, &new_indent);
+            contents += &condition_to_xml(condition, &new_indent);
+            contents += &block_to_xml(then_statements, &new_indent);
+            if let Some(else_statements) = else_statements {
+                contents += &keyword(Keyword::Else, &new_indent);
+                contents += &block_to_xml(else_statements, &new_indent);
+            }
+            return element("ifStatement", &contents, indent);
+        }
+        Statement::While {condition, statements, ..} => {
+            let mut contents = keyword(Keyword::While, &new_indent);
+            contents += &condition_to_xml(condition, &new_indent);
+            contents += &block_to_xml(statements, &new_indent);
+            return element("whileStatement", &contents, indent);
+        }
+        Statement::Do {call, ..} => {
+            let mut contents = keyword(Keyword::Do, &new_indent);
+            contents += &subroutine_call_to_xml(call, &new_indent);
+            contents += &symbol(';', &new_indent);
+            return element("doStatement", &contents, indent);
+        }
+        Statement::Return {value, ..} => {
+            let mut contents = keyword(Keyword::Return, &new_indent);
+            if let Some(value) = value {
+                contents += &expression_to_xml(value, &new_indent);
+            }
+            contents += &symbol(';', &new_indent);
+            return element("returnStatement", &contents, indent);
+        }
+        // jackanalyzer parses without --extensions
+        Statement::For {..} | Statement::Break {..} | Statement::Continue {..} => unreachable!("for, break and continue are parsed only with --extensions"),
+    }
+}
+
+// "(" expression ")"
+fn condition_to_xml(condition: &Expression, indent: &str) -> String {
+    return symbol('(', indent) + &expression_to_xml(condition, indent) + &symbol(')', indent);
+}
+
+// "{" statements "}"
+fn block_to_xml(statements: &[Statement], indent: &str) -> String {
+    return symbol('{', indent) + &statements_to_xml(statements, indent) + &symbol('}', indent);
+}
+
+// ---------- expression ----------
+fn expression_to_xml(expression: &Expression, indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    let mut contents = term_to_xml(&expression.term, &new_indent);
+    for (operator, term) in &expression.operations {
+        contents += &symbol(*operator, &new_indent);
+        contents += &term_to_xml(term, &new_indent);
+    }
+    return element("expression", &contents, indent);
+}
+
+fn term_to_xml(term: &Term, indent: &str) -> String {
+    let new_indent = format!("{}\t", indent);
+    let contents = match term {
+        Term::IntConst(integer, _) => token(&Token::IntConst(*integer), &new_indent),
+        Term::StringConst(string, _) => token(&Token::StringConst(string.to_string()), &new_indent),
+        Term::KeywordConst(keyword_const, _) => keyword(*keyword_const, &new_indent),
+        Term::Var(name, _) => identifier(name, &new_indent),
+        Term::ArrayElement(name, index, _) => {
+            identifier(name, &new_indent) + &symbol('[', &new_indent) + &expression_to_xml(index, &new_indent) + &symbol(']', &new_indent)
+        }
+        Term::Parenthesized(expression, _) => condition_to_xml(expression, &new_indent),
+        Term::Unary(operator, term, _) => symbol(*operator, &new_indent) + &term_to_xml(term, &new_indent),
+        Term::Call(call) => subroutine_call_to_xml(call, &new_indent),
+    };
+    return element("term", &contents, indent);
+}
+
+// ((className | varName) ".")? subroutineName "(" expressionList ")": the tokens without an element of its own
+fn subroutine_call_to_xml(call: &SubroutineCall, indent: &str) -> String {
+    let mut xml = "".to_string();
+    if let Some(receiver) = &call.receiver {
+        xml += &identifier(receiver, indent);
+        xml += &symbol('.', indent);
+    }
+    xml += &identifier(&call.name, indent);
+    xml += &symbol('(', indent);
+
+    // expressionList :: (expression ("," expression)*)?
+    let new_indent = format!("{}\t", indent);
+    let mut arguments = "".to_string();
+    for (i, argument) in call.arguments.iter().enumerate() {
+        if i != 0 {
+            arguments += &symbol(',', &new_indent);
+        }
+        arguments += &expression_to_xml(argument, &new_indent);
+    }
+    xml += &element("expressionList", &arguments, indent);
+    xml += &symbol(')', indent);
+    return xml;
+}
+
+// ---------- tokens ----------
+fn element(name: &str, contents: &str, indent: &str) -> String {
+    return format!("{}<{}>\n{}{}</{}>\n", indent, name, contents, indent, name);
+}
+
+fn token(token: &Token, indent: &str) -> String {
+    return format!("{}{}\n", indent, token.to_xml());
+}
+
+fn keyword(keyword: Keyword, indent: &str) -> String {
+    return token(&Token::Keyword(keyword), indent);
+}
+
+fn symbol(symbol: char, indent: &str) -> String {
+    return token(&Token::Symbol(symbol), indent);
+}
+
+fn identifier(name: &str, indent: &str) -> String {
+    return token(&Token::Identifier(name.to_string()), indent);
+}
+
+// int, char, boolean and void are keywords, and class names are identifiers
+fn var_type(var_type: &str, indent: &str) -> String {
+    match Keyword::from_word(var_type, false) {
+        Some(type_keyword) => return keyword(type_keyword, indent),
+        None => return identifier(var_type, indent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jack_frontend::lexical_analysis::Lexicon;
+    use jack_frontend::parser::parse_class;
+
+    #[test]
+    fn tokens_are_written_back_in_order() {
+        let source = "class Main { function void main() { do Output.printInt(-(1 < 2)); return; } }";
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", source, false);
+        let (class, syntax_errors) = parse_class(&lexicons, "Main.jack", false, false);
+        assert!(lexical_errors.is_empty() && syntax_errors.is_empty(), "invalid test code");
+        let xml = class_to_xml(&class);
+        let words: Vec<&str> = xml.split_whitespace().collect();
+        let expected = "<class> <keyword> class </keyword> <identifier> Main </identifier> <symbol> { </symbol>
+            <subroutineDec> <keyword> function </keyword> <keyword> void </keyword> <identifier> main </identifier>
+            <symbol> ( </symbol> <parameterList> </parameterList> <symbol> ) </symbol>
+            <subroutineBody> <symbol> { </symbol> <statements>
+            <doStatement> <keyword> do </keyword> <identifier> Output </identifier> <symbol> . </symbol> <identifier> printInt </identifier>
+            <symbol> ( </symbol> <expressionList> <expression> <term> <symbol> - </symbol> <term> <symbol> ( </symbol>
+            <expression> <term> <integerConstant> 1 </integerConstant> </term> <symbol> &lt; </symbol> <term> <integerConstant> 2 </integerConstant> </term> </expression>
+            <symbol> ) </symbol> </term> </term> </expression> </expressionList> <symbol> ) </symbol> <symbol> ; </symbol> </doStatement>
+            <returnStatement> <keyword> return </keyword> <symbol> ; </symbol> </returnStatement>
+            </statements> <symbol> } </symbol> </subroutineBody> </subroutineDec> <symbol> } </symbol> </class>";
+        assert_eq!(words, expected.split_whitespace().collect::<Vec<&str>>());
+    }
+}
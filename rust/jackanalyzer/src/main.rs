@@ -1,27 +1,44 @@
-// Jack lang's compiler
-// コンピュータシステムの理論と実装 §10, §11
+// jack lang's syntax analyzer: foo.jack -> foo.xml (the parse tree)
+// コンピュータシステムの理論と実装 §10
 
-use std::env;
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
 
-mod lexical_analysis;
-use crate::lexical_analysis::Lexicon;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
 
-mod parser;
-use crate::parser::parse_class;
+use jack_frontend::diagnostic::report;
+use jack_frontend::lexical_analysis::Lexicon;
+use jack_frontend::parser::parse_class;
+use tool_output::{Marker, OutDir};
 
-mod parser_to_xml;
+mod ast_to_xml;
 
 fn main() {
-    // get path from command line
+    // get path and options from command line
+    //   -o dir, --out-dir dir: writes foo.xml into dir instead of next to foo.jack (-o -: into stdout)
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        panic!("input path: ./jackanalyzer path/to/dir || path/to/foo.jack");
+    let mut out_dir = OutDir::NextToInput;
+    let mut input_path = None;
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        if arg == "-o" || arg == "--out-dir" {
+            match args_iter.next() {
+                Some(dir) => out_dir = OutDir::from_arg(dir),
+                None => panic!("error: {} needs an output directory (or - for stdout)", arg),
+            }
+        } else {
+            input_path = Some(PathBuf::from(arg));
+        }
     }
-    let path = PathBuf::from(&args[1]);
+    let path = match input_path {
+        Some(path) => path,
+        None => panic!("input path: ./jackanalyzer [-o out_dir|-] path/to/dir || path/to/foo.jack"),
+    };
 
+    let mut success = true;
     if path.is_dir() {
         // parse all .jack file in the directory
         let directory = path.read_dir().expect("couldn't open the directory");
@@ -30,8 +47,7 @@ fn main() {
                 let path_of_entry = entry.path();
                 if let Some(extension) = path_of_entry.extension() {
                     if extension == "jack" {
-                        parser(&path_of_entry);
-//                        tokenize_to_xml(&path_of_entry);
+                        success &= analyzer(&path_of_entry, &out_dir);
                     }
                 }
             } else {
@@ -42,52 +58,32 @@ fn main() {
     } else if let Some(extension) = path.extension() {
         // parse .jack file
         if extension == "jack" {
-            parser(&path);
-//            tokenize_to_xml(&path);
+            success &= analyzer(&path, &out_dir);
         } else {
             panic!("error: invalid path: input ./jackanalyzer path/to/foo.jack");
         }
     } else {
         panic!("error: invalid path: input ./jackanalyzer path/to/foo.jack");
     }
-}
-
-
-fn write_to_xmlfile(path: &PathBuf, contents: String) {
-    let mut new_path = path.clone();
-    new_path.set_extension("xml");
-    let mut xmlfile = match File::create(&new_path) {
-        Err(why) => panic!("couldn't create {}: {}", new_path.display(), why),
-        Ok(file) => file,
-    };
-    writeln!(xmlfile, "{}", contents).expect("couldn't write to file");
-}
-
-// functions for unit test 
-fn tokenize_to_xml(path: &PathBuf) {
-    let lex_vec = Lexicon::lexical_analysis(path);
-    let mut contents = "<tokens>\n".to_string();
-    for lex in lex_vec {
-        contents += &Lexicon::lex_to_xml(&lex);
-        contents += "\n";
+    if !success {
+        process::exit(1);
     }
-    contents += "</tokens>";
-    write_to_xmlfile(path, contents);
 }
 
-fn parser(path: &PathBuf) {
-    let lex_vec = Lexicon::lexical_analysis(path);
+// all errors of foo.jack are printed: returns false if there are errors (foo.xml is not written)
+fn analyzer(path: &Path, out_dir: &OutDir) -> bool {
     let filename = path.file_name().expect("error: invalid filename").to_str().expect("error: invalid filename");
-    let contents = parser_to_xml::parse_class(&lex_vec, filename);
-    write_to_xmlfile(path, contents);
-}
-
-fn write_to_xmlfile(path: &PathBuf, contents: String) {
-    let mut new_path = path.clone();
-    new_path.set_extension("xml");
-    let mut xmlfile = match File::create(&new_path) {
-        Err(why) => panic!("couldn't create {}: {}", new_path.display(), why),
-        Ok(file) => file,
+    let source = match fs::read_to_string(path) {
+        Err(why) => panic!("couldn't read {}: {}", path.display(), why),
+        Ok(source) => source,
     };
-    writeln!(xmlfile, "{}", contents).expect("couldn't write to file");
-}
\ No newline at end of file
+    let (lex_vec, mut diagnostics) = Lexicon::tokenize(filename, &source, false);
+    let (class, mut parse_diagnostics) = parse_class(&lex_vec, filename, false, false);
+    diagnostics.append(&mut parse_diagnostics);
+    if !report(&mut diagnostics, &source) {
+        return false;
+    }
+    let contents = ast_to_xml::class_to_xml(&class);
+    out_dir.write(&out_dir.output_path(path, "xml"), &format!("{}\n", contents), "jackanalyzer", Marker::NoComment);
+    return true;
+}
@@ -0,0 +1,411 @@
+// code generation package
+// translating the abstract syntax tree of a class into vm code
+// コンピュータシステムの理論と実装 §11
+
+use jack_frontend::ast::{Class, ClassVarKind, Expression, Statement, SubroutineCall, SubroutineDec, SubroutineKind, Term};
+use jack_frontend::lexical_analysis::{char_code, Keyword, Span};
+use jack_frontend::program_info::ProgramInfo;
+use jack_frontend::symbol_table::SymbolTable;
+
+struct Data<'a> {
+    class: String,
+    field_vars_count: usize,
+    conditional_branch_count: usize,
+    // (continue label, break label) of the enclosing loops
+    loops: Vec<(String, String)>,
+    symbol_table: SymbolTable,
+    filename: String,
+    program_info: &'a ProgramInfo,
+}
+
+// ---------- class ----------
+// program_info: kinds of subroutines decide whether the object is passed as argument 0
+pub fn compile_class(class: &Class, filename: &str, program_info: &ProgramInfo) -> String {
+    let mut data = Data {
+        class: class.name.to_string(),
+        field_vars_count: 0,
+        conditional_branch_count: 0,
+        loops: vec![],
+        symbol_table: SymbolTable::new(),
+        filename: filename.to_string(),
+        program_info,
+    };
+    define(&class.name, "class", &"none".to_string(), &class.span, &mut data);
+
+    // classVarDec*
+    for var_dec in &class.var_decs {
+        for (name, span) in &var_dec.names {
+            define(name, var_dec.kind.name(), &var_dec.var_type, span, &mut data);
+            if var_dec.kind == ClassVarKind::Field {
+                data.field_vars_count += 1;
+            }
+        }
+    }
+
+    // subroutineDec*
+    let mut class_contents = "".to_string();
+    for subroutine in &class.subroutines {
+        class_contents += &compile_subroutine_dec(subroutine, &mut data);
+    }
+    return class_contents;
+}
+
+fn compile_subroutine_dec(subroutine: &SubroutineDec, data: &mut Data) -> String {
+    // clear local symbol_table
+    data.symbol_table.clear_local_table();
+    define(&subroutine.name, "subroutine", &subroutine.return_type, &subroutine.span, data);
+
+    // when return_type is method, 'argument 0' is assigned to the pointer of object
+    if subroutine.kind == SubroutineKind::Method {
+        let class_name = data.class.to_string();
+        define(&"this".to_string(), "arg", &class_name, &subroutine.span, data);
+    }
+    for parameter in &subroutine.parameters {
+        define(&parameter.name, "arg", &parameter.var_type, &parameter.span, data);
+    }
+    for var_dec in &subroutine.var_decs {
+        for (name, span) in &var_dec.names {
+            define(name, "var", &var_dec.var_type, span, data);
+        }
+    }
+    let subroutine_body = compile_statements(&subroutine.statements, data);
+
+    // code generation
+    let mut code = format!("function {}.{} {}\n", &data.class, subroutine.name, data.symbol_table.var_counter);
+    if subroutine.kind == SubroutineKind::Constructor {
+        code += &format!("\tpush constant {}\n\tcall Memory.alloc 1\n\tpop pointer 0\n", data.field_vars_count);
+    } else if subroutine.kind == SubroutineKind::Method {
+        code += "\tpush argument 0\n\tpop pointer 0\n";
+    }
+    return code + &subroutine_body;
+}
+
+// ---------- statements ----------
+fn compile_statements(statements: &Vec<Statement>, data: &mut Data) -> String {
+    let mut code = "".to_string();
+    for statement in statements {
+        code += &compile_statement(statement, data);
+    }
+    return code;
+}
+
+fn compile_statement(statement: &Statement, data: &mut Data) -> String {
+    match statement {
+        Statement::Let {name, index, value, span} => {
+            let var_name = identifier_use(name, span, data);
+            let array_index = index.as_ref().map(|index| compile_expression(index, data));
+            let expression = compile_expression(value, data);
+            if let Some(array_index) = array_index {
+                return format!("\tpush {}\n{}\tadd\n{}\tpop temp 0\n\tpop pointer 1\n\tpush temp 0\n\tpop that 0\n", var_name, array_index, expression);
+            } else {
+                return format!("{}\tpop {}\n", expression, var_name);
+            }
+        }
+        Statement::If {condition, then_statements, else_statements, ..} => {
+            let condition = compile_expression(condition, data);
+            let if_statement = compile_statements(then_statements, data);
+            let else_code = else_statements.as_ref().map(|statements| compile_statements(statements, data));
+
+            let mut code = format!("{}\tnot\n\tif-goto {}_FALSECASE_{}\n{}", condition, &data.class, data.conditional_branch_count, if_statement);
+            if else_code.is_some() {
+                code += &format!("\tgoto {}_TRUECASE_{}\n", &data.class, data.conditional_branch_count);
+            }
+            code += &format!("label {}_FALSECASE_{}\n", &data.class, data.conditional_branch_count);
+            if let Some(else_statements) = else_code {
+                code += &format!("{}label {}_TRUECASE_{}\n", else_statements, &data.class, data.conditional_branch_count);
+            }
+            data.conditional_branch_count += 1;
+            return code;
+        }
+        Statement::While {condition, statements, ..} => {
+            // the number of the loop is counted after its body: it is known before compiling the body for break and continue
+            let number = data.conditional_branch_count + count_branches(statements);
+            let while_label = format!("{}_WHILE_{}", &data.class, number);
+            let break_label = format!("{}_BREAK_{}", &data.class, number);
+            let condition = compile_expression(condition, data);
+            data.loops.push((while_label.to_string(), break_label.to_string()));
+            let statements = compile_statements(statements, data);
+            data.loops.pop();
+
+            let code = format!("label {}\n{}\tnot\n\tif-goto {}\n{}\tgoto {}\nlabel {}\n", while_label, condition, break_label, statements, while_label, break_label);
+            data.conditional_branch_count += 1;
+            return code;
+        }
+        Statement::For {init, condition, update, statements, ..} => {
+            // init; while (condition) { statements; update; }, and continue jumps to the update
+            let number = data.conditional_branch_count + count_branches(statements);
+            let while_label = format!("{}_WHILE_{}", &data.class, number);
+            let continue_label = format!("{}_CONTINUE_{}", &data.class, number);
+            let break_label = format!("{}_BREAK_{}", &data.class, number);
+            let mut code = match init {
+                Some(init) => compile_statement(init, data),
+                None => "".to_string(),
+            };
+            code += &format!("label {}\n", while_label);
+            if let Some(condition) = condition {
+                code += &format!("{}\tnot\n\tif-goto {}\n", compile_expression(condition, data), break_label);
+            }
+            data.loops.push((continue_label.to_string(), break_label.to_string()));
+            code += &compile_statements(statements, data);
+            data.loops.pop();
+            code += &format!("label {}\n", continue_label);
+            if let Some(update) = update {
+                code += &compile_statement(update, data);
+            }
+            code += &format!("\tgoto {}\nlabel {}\n", while_label, break_label);
+            data.conditional_branch_count += 1;
+            return code;
+        }
+        Statement::Break {span} => {
+            match data.loops.last() {
+                Some((_, break_label)) => return format!("\tgoto {}\n", break_label),
+                // reported by the parser
+                None => unreachable!("'break' outside of a loop: in {} line {} column {}", data.filename, span.line, span.column),
+            }
+        }
+        Statement::Continue {span} => {
+            match data.loops.last() {
+                Some((continue_label, _)) => return format!("\tgoto {}\n", continue_label),
+                // reported by the parser
+                None => unreachable!("'continue' outside of a loop: in {} line {} column {}", data.filename, span.line, span.column),
+            }
+        }
+        Statement::Do {call, ..} => {
+            return compile_subroutine_call(call, data) + "\tpop temp 0\n";
+        }
+        Statement::Return {value, ..} => {
+            let expression = match value {
+                Some(value) => compile_expression(value, data),
+                None => "\tpush constant 0\n".to_string(),
+            };
+            return format!("{}\treturn\n", expression);
+        }
+    }
+}
+
+// if, while and for statements in the statements (they are numbered by conditional_branch_count)
+fn count_branches(statements: &Vec<Statement>) -> usize {
+    let mut count = 0;
+    for statement in statements {
+        count += match statement {
+            Statement::If {then_statements, else_statements, ..} => 1 + count_branches(then_statements) + else_statements.as_ref().map_or(0, count_branches),
+            Statement::While {statements, ..} | Statement::For {statements, ..} => 1 + count_branches(statements),
+            _ => 0,
+        };
+    }
+    return count;
+}
+
+// ---------- expression ----------
+fn compile_subroutine_call(call: &SubroutineCall, data: &mut Data) -> String {
+    let mut expression_list = "".to_string();
+    for argument in &call.arguments {
+        expression_list += &compile_expression(argument, data);
+    }
+    let argument_counter = call.arguments.len();
+
+    let name = match &call.receiver {
+        // subroutineName "(" expressionList ")" := (this.) method(arguments) | function(arguments) of this class
+        None => match kind_of(&data.class, &call.name, data) {
+            Some(SubroutineKind::Function) | Some(SubroutineKind::Constructor) => {
+                return format!("{}\tcall {}.{} {}\n", expression_list, &data.class, call.name, argument_counter);
+            }
+            _ => return format!("\tpush pointer 0\n{}\tcall {}.{} {}\n", expression_list, &data.class, call.name, argument_counter + 1),
+        },
+        Some(name) => name,
+    };
+
+    // (className | varName) "." subroutineName "(" expressionList ")": a name of a subroutine or the class is not a variable
+    let primitive_type = ["int", "char", "boolean", "void"];
+    if let Some(info) = data.symbol_table.find(name).filter(|info| info.category != "class" && info.category != "subroutine") {
+        if primitive_type.contains(&info.var_type.as_str()) {
+            // reported by the resolver
+            unreachable!("method is called on '{}' of type {}: in {} line {} column {}", name, info.var_type, data.filename, call.span.line, call.span.column);
+        } else if let Some(SubroutineKind::Function) | Some(SubroutineKind::Constructor) = kind_of(&info.var_type, &call.name, data) {
+            // varName.function(arguments): the object is not passed
+            return format!("{}\tcall {}.{} {}\n", expression_list, info.var_type, call.name, argument_counter);
+        } else {
+            // varName.method(arguments)
+            return format!("\tpush {}\n{}\tcall {}.{} {}\n", info.index, expression_list, info.var_type, call.name, argument_counter + 1);
+        }
+    }
+    if kind_of(name, &call.name, data) == Some(SubroutineKind::Method) {
+        // reported by the resolver
+        unreachable!("method '{}.{}' is called as a function: in {} line {} column {}", name, call.name, data.filename, call.span.line, call.span.column);
+    }
+    // className.function(arguments)
+    return format!("{}\tcall {}.{} {}\n", expression_list, name, call.name, argument_counter);
+}
+
+// kind of a known subroutine (classes of the program and the Jack OS)
+fn kind_of(class_name: &str, subroutine_name: &str, data: &Data) -> Option<SubroutineKind> {
+    return data.program_info.find(class_name, subroutine_name).map(|info| info.kind);
+}
+
+fn compile_expression(expression: &Expression, data: &mut Data) -> String {
+    let mut code = compile_term(&expression.term, data);
+    for (operator, term) in &expression.operations {
+        code += &compile_term(term, data);
+        code += match operator {
+            '+' => "\tadd\n",
+            '-' => "\tsub\n",
+            '*' => "\tcall Math.multiply 2\n",
+            '/' => "\tcall Math.divide 2\n",
+            '&' => "\tand\n",
+            '|' => "\tor\n",
+            '<' => "\tlt\n",
+            '>' => "\tgt\n",
+            '=' => "\teq\n",
+            // the parser accepts only these operators
+            _ => unreachable!("invalid operator '{}'", operator),
+        };
+    }
+    return code;
+}
+
+fn compile_term(term: &Term, data: &mut Data) -> String {
+    match term {
+        Term::IntConst(integer, _) => {
+            return format!("\tpush constant {}\n", integer);
+        }
+        Term::StringConst(string, span) => {
+            let length = string.chars().count();
+            let mut code = format!("\tpush constant {}\n\tcall String.new 1\n", length);
+            for c in string.chars() {
+                let char_number = match char_code(c) {
+                    Some(number) => number,
+                    // reported by the lexer
+                    None => unreachable!("'{}' is not in the Hack character set: in {} line {} column {}", c, data.filename, span.line, span.column),
+                };
+                code += &format!("\tpush constant {}\n\tcall String.appendChar 2\n", char_number);
+            }
+            return code;
+        }
+        Term::KeywordConst(keyword, _) => {
+            if *keyword == Keyword::True {
+                return "\tpush constant 1\n\tneg\n".to_string();
+            } else if *keyword == Keyword::This {
+                return "\tpush pointer 0\n".to_string();
+            } else {
+                // false, null
+                return "\tpush constant 0\n".to_string();
+            }
+        }
+        Term::Var(name, span) => {
+            let var_index = identifier_use(name, span, data);
+            return format!("\tpush {}\n", var_index);
+        }
+        Term::ArrayElement(name, index, span) => {
+            let var_index = identifier_use(name, span, data);
+            let expression = compile_expression(index, data);
+            return format!("\tpush {}\n{}\tadd\n\tpop pointer 1\n\tpush that 0\n", var_index, expression);
+        }
+        Term::Parenthesized(expression, _) => {
+            return compile_expression(expression, data);
+        }
+        Term::Unary(operator, term, _) => {
+            let term = compile_term(term, data);
+            if *operator == '-' {
+                return term + "\tneg\n";
+            } else {
+                return term + "\tnot\n";
+            }
+        }
+        Term::Call(call) => {
+            return compile_subroutine_call(call, data);
+        }
+    }
+}
+
+// subroutine
+// duplicate declarations are reported by the resolver before code generation
+fn define(name: &String, category: &str, var_type: &String, span: &Span, data: &mut Data) {
+    if let Err(message) = data.symbol_table.define(name, category, var_type) {
+        unreachable!("{}: in {} line {} column {}", message, data.filename, span.line, span.column);
+    }
+}
+
+fn identifier_use(name: &String, span: &Span, data: &Data) -> String {
+    let symbol_info = match data.symbol_table.find(name) {
+        Some(info) => info,
+        // reported by the type checker
+        None => unreachable!("variable '{}' is not defined: in {} line {} column {}", name, data.filename, span.line, span.column),
+    };
+    return symbol_info.index.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jack_frontend::lexical_analysis::Lexicon;
+    use jack_frontend::parser::parse_class;
+
+    // vm code of "class Main { body }" (with --extensions)
+    fn compile(body: &str) -> String {
+        let source = format!("class Main {{\n{}\n}}\n", body);
+        let (lexicons, lexical_errors) = Lexicon::tokenize("Main.jack", &source, true);
+        let (class, syntax_errors) = parse_class(&lexicons, "Main.jack", true, false);
+        assert!(lexical_errors.is_empty() && syntax_errors.is_empty(), "invalid test code");
+        let mut program_info = ProgramInfo::new();
+        program_info.add_os_api();
+        program_info.add_class(&class);
+        return compile_class(&class, "Main.jack", &program_info);
+    }
+
+    #[test]
+    fn continue_in_for_jumps_to_the_update() {
+        let code = compile("function int f() {
+            var int i, sum;
+            let sum = 0;
+            for (let i = 0; i < 10; let i = i + 1) {
+                if (i = 3) { continue; }
+                let sum = sum + i;
+            }
+            return sum;
+        }");
+        let continue_label = code.lines().find_map(|line| line.strip_prefix("label Main_CONTINUE_")).expect("no continue label");
+        let number = continue_label.to_string();
+        let goto_continue = code.find(&format!("\tgoto Main_CONTINUE_{}\n", number)).expect("no jump to the continue label");
+        let update = format!("label Main_CONTINUE_{0}\n\tpush local 0\n\tpush constant 1\n\tadd\n\tpop local 0\n\tgoto Main_WHILE_{0}\nlabel Main_BREAK_{0}\n", number);
+        let update_position = code.find(&update).expect("the update doesn't follow the continue label");
+        assert!(goto_continue < update_position, "{}", code);
+    }
+
+    #[test]
+    fn labels_are_unique_in_nested_statements() {
+        let code = compile("function void f(int n) {
+            var int i, j;
+            for (let i = 0; i < n; let i = i + 1) {
+                let j = 0;
+                while (j < n) {
+                    if (j = i) { let j = j + 1; continue; } else { if (j > 5) { break; } }
+                    for (;;) { break; }
+                    while (false) { }
+                    let j = j + 1;
+                }
+                if (i > 3) { break; } else if (i = 1) { continue; }
+            }
+            while (n > 0) { let n = n - 1; }
+            return;
+        }");
+        let labels: Vec<&str> = code.lines().filter_map(|line| line.strip_prefix("label ")).collect();
+        for (i, label) in labels.iter().enumerate() {
+            assert!(!labels[i + 1..].contains(label), "label {} is defined twice:\n{}", label, code);
+        }
+        for line in code.lines() {
+            if let Some(target) = line.trim().strip_prefix("goto ").or(line.trim().strip_prefix("if-goto ")) {
+                assert!(labels.contains(&target), "label {} is not defined:\n{}", target, code);
+            }
+        }
+    }
+
+    #[test]
+    fn receiver_named_like_a_subroutine_is_a_class() {
+        let code = compile("function void Output() { return; }
+        function void f() {
+            do Output.printInt(1);
+            return;
+        }");
+        assert!(code.contains("\tpush constant 1\n\tcall Output.printInt 1\n"), "{}", code);
+    }
+}
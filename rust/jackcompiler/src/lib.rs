@@ -0,0 +1,65 @@
+// compiling jack to vm code: shared by jackcompiler and the jack build driver
+// コンピュータシステムの理論と実装 §10, §11
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use jack_frontend::ast::Class;
+use jack_frontend::control_flow::check_control_flow;
+use jack_frontend::diagnostic::Diagnostic;
+use jack_frontend::lexical_analysis::Lexicon;
+use jack_frontend::parser::parse_class;
+use jack_frontend::program_info::ProgramInfo;
+use jack_frontend::resolver::resolve_class;
+use jack_frontend::type_checker::{check_class, TypeCheckLevel};
+use jack_frontend::usage::check_usage;
+
+pub mod codegen;
+
+// a .jack file through the passes of the compiler
+pub struct Unit {
+    pub path: PathBuf,
+    pub filename: String,
+    pub source: String,
+    pub class: Class,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+// parsing and semantic analysis of all files: the diagnostics are left in the units (not printed yet)
+// whole_program: all classes except the Jack OS are in paths, so undefined classes are errors
+// precedence: operators are evaluated by the conventional precedence instead of left to right
+pub fn analyze(paths: &Vec<PathBuf>, whole_program: bool, extensions: bool, precedence: bool, type_check: TypeCheckLevel) -> (Vec<Unit>, ProgramInfo) {
+    // parsing
+    let mut units = vec![];
+    for path in paths {
+        let filename = path.file_name().expect("error: invalid filename").to_str().expect("error: invalid filename").to_string();
+        let source = match fs::read_to_string(path) {
+            Err(why) => panic!("couldn't read {}: {}", path.display(), why),
+            Ok(source) => source,
+        };
+        let (lex_vec, mut diagnostics) = Lexicon::tokenize(&filename, &source, extensions);
+        let (class, mut parse_diagnostics) = parse_class(&lex_vec, &filename, extensions, precedence);
+        diagnostics.append(&mut parse_diagnostics);
+        units.push(Unit {path: path.clone(), filename, source, class, diagnostics});
+    }
+
+    // semantic analysis with the signatures of all classes and the Jack OS
+    let mut program_info = ProgramInfo::new();
+    program_info.add_os_api();
+    for unit in &units {
+        program_info.add_class(&unit.class);
+    }
+    for unit in &mut units {
+        // the parser reports warnings too: the analysis is skipped only after syntax errors
+        if !unit.diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+            unit.diagnostics.append(&mut resolve_class(&unit.class, &program_info, whole_program));
+            unit.diagnostics.append(&mut check_class(&unit.class, &program_info, type_check));
+            unit.diagnostics.append(&mut check_control_flow(&unit.class));
+            unit.diagnostics.append(&mut check_usage(&unit.class, &unit.source));
+        }
+    }
+    return (units, program_info);
+}
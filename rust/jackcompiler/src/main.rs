@@ -1,25 +1,61 @@
 // jack lang's compiler
 // コンピュータシステムの理論と実装 §10, §11
 
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
 use std::env;
-use std::fs::File;
-use std::io::Write;
 use std::path::PathBuf;
+use std::process;
 
-mod lexical_analysis;
-use crate::lexical_analysis::Lexicon;
-
-mod parser;
-use crate::parser::compile_starter;
+use jack_frontend::diagnostic::report;
+use tool_output::{Marker, OutDir};
+use jack_frontend::type_checker::TypeCheckLevel;
+use jackcompiler::analyze;
+use jackcompiler::codegen::compile_class;
 
 fn main() {
-    // get path from command line
+    // get path and options from command line
+    //   --extensions: character literals 'A', hex literals 0x1F, and statements for, break, continue and else if
+    //   --precedence: * / over + - over < > = over & | (Jack evaluates operators left to right by default)
+    //   --type-check off|warn|error|strict: how type problems are reported (default: error)
+    //   -o dir, --out-dir dir: writes foo.vm into dir instead of next to foo.jack (-o -: into stdout)
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        panic!("input path: ./jackanalyzer path/to/dir || path/to/foo.jack");
+    let mut extensions = false;
+    let mut precedence = false;
+    let mut type_check = TypeCheckLevel::Error;
+    let mut out_dir = OutDir::NextToInput;
+    let mut input_path = None;
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        if arg == "--extensions" {
+            extensions = true;
+        } else if arg == "--precedence" {
+            precedence = true;
+        } else if arg == "-o" || arg == "--out-dir" {
+            match args_iter.next() {
+                Some(dir) => out_dir = OutDir::from_arg(dir),
+                None => panic!("error: {} needs an output directory (or - for stdout)", arg),
+            }
+        } else if arg == "--type-check" {
+            type_check = match args_iter.next().map(|level| level.as_str()) {
+                Some("off") => TypeCheckLevel::Off,
+                Some("warn") => TypeCheckLevel::Warn,
+                Some("error") => TypeCheckLevel::Error,
+                Some("strict") => TypeCheckLevel::Strict,
+                _ => panic!("error: --type-check needs off, warn, error or strict"),
+            };
+        } else {
+            input_path = Some(PathBuf::from(arg));
+        }
     }
-    let path = PathBuf::from(&args[1]);
+    let path = match input_path {
+        Some(path) => path,
+        None => panic!("input path: ./jackcompiler [--extensions] [--precedence] [--type-check off|warn|error|strict] [-o out_dir|-] path/to/dir || path/to/foo.jack"),
+    };
 
+    let mut paths = vec![];
+    let whole_program = path.is_dir();
     if path.is_dir() {
         // parse all .jack file in the directory
         let directory = path.read_dir().expect("couldn't open the directory");
@@ -28,7 +64,7 @@ fn main() {
                 let path_of_entry = entry.path();
                 if let Some(extension) = path_of_entry.extension() {
                     if extension == "jack" {
-                        compiler(&path_of_entry);
+                        paths.push(path_of_entry);
                     }
                 }
             } else {
@@ -36,31 +72,36 @@ fn main() {
                 continue;
             }
         }
+        paths.sort();
     } else if let Some(extension) = path.extension() {
         // parse .jack file
         if extension == "jack" {
-            compiler(&path)
+            paths.push(path);
         } else {
-            panic!("error: invalid path: input ./jackanalyzer path/to/foo.jack");
+            panic!("error: invalid path: input ./jackcompiler path/to/foo.jack");
         }
     } else {
-        panic!("error: invalid path: input ./jackanalyzer path/to/foo.jack");
+        panic!("error: invalid path: input ./jackcompiler path/to/foo.jack");
     }
-}
 
-fn compiler(path: &PathBuf) {
-    let lex_vec = Lexicon::lexical_analysis(path);
-    let filename = path.file_name().expect("error: invalid filename").to_str().expect("error: invalid filename");
-    let contents = compile_starter(lex_vec, filename);
-    write_to_vmfile(path, contents);
+    if !compiler(&paths, whole_program, extensions, precedence, type_check, &out_dir) {
+        process::exit(1);
+    }
 }
 
-fn write_to_vmfile(path: &PathBuf, contents: String) {
-    let mut new_path = path.clone();
-    new_path.set_extension("vm");
-    let mut vmfile = match File::create(&new_path) {
-        Err(why) => panic!("couldn't create {}: {}", new_path.display(), why),
-        Ok(file) => file,
-    };
-    writeln!(vmfile, "{}", contents).expect("couldn't write to file");
+// all files are compiled even if some of them have errors: returns false if there are errors (their foo.vm is not written)
+fn compiler(paths: &Vec<PathBuf>, whole_program: bool, extensions: bool, precedence: bool, type_check: TypeCheckLevel, out_dir: &OutDir) -> bool {
+    let (mut units, program_info) = analyze(paths, whole_program, extensions, precedence, type_check);
+
+    // code generation
+    let mut success = true;
+    for unit in &mut units {
+        if !report(&mut unit.diagnostics, &unit.source) {
+            success = false;
+            continue;
+        }
+        let contents = compile_class(&unit.class, &unit.filename, &program_info);
+        out_dir.write(&out_dir.output_path(&unit.path, "vm"), &format!("{}\n", contents), "jackcompiler", Marker::Comment("//"));
+    }
+    return success;
 }
@@ -0,0 +1,138 @@
+// where the tools write their outputs: next to the input (default), into a directory (-o dir) or into stdout (-o -)
+// an existing file is overwritten only if it was generated by the tools,
+// so that hand-written files (e.g. foo.asm of the projects in §4, the expected foo.xml of §10) are never lost
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// the list of files without "generated by" comment, in the directory of the files
+const GENERATED_LIST: &str = ".generated";
+
+pub enum OutDir {
+    NextToInput,
+    Directory(PathBuf),
+    Stdout,
+}
+
+// how a generated file is recognized
+#[derive(Clone, Copy)]
+pub enum Marker {
+    // the first line is a "generated by" comment: "//" (.vm, .asm, .c), ";;" (.wat)
+    Comment(&'static str),
+    // no comment can be written (.hack, and .xml which must be the same as the reference files):
+    // the file name is recorded in the .generated file of the directory
+    NoComment,
+}
+
+impl OutDir {
+    // the argument of -o/--out-dir
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            return OutDir::Stdout;
+        } else {
+            return OutDir::Directory(PathBuf::from(arg));
+        }
+    }
+
+    // path/to/foo.jack (or path/to/dir) -> out_dir/foo.extension (path/to/foo.extension by default)
+    pub fn output_path(&self, input: &Path, extension: &str) -> PathBuf {
+        let mut path = match self {
+            OutDir::Directory(out_dir) => {
+                // "." has no name: the name of the current directory
+                let input = match input.file_name() {
+                    Some(_) => input.to_path_buf(),
+                    None => input.canonicalize().unwrap_or(input.to_path_buf()),
+                };
+                match input.file_name() {
+                    Some(name) => out_dir.join(name),
+                    None => panic!("error: invalid path {}", input.display()),
+                }
+            }
+            _ => input.to_path_buf(),
+        };
+        path.set_extension(extension);
+        return path;
+    }
+
+    // tool: the name written into the "generated by" comment
+    pub fn write(&self, path: &Path, contents: &str, tool: &str, marker: Marker) {
+        if let OutDir::Stdout = self {
+            print!("{}", contents);
+            return;
+        }
+        if !is_generated(path, marker) {
+            panic!("error: refused to overwrite {}: it is not generated by the tools (remove it, or write into another directory with -o dir)", path.display());
+        }
+        if let OutDir::Directory(out_dir) = self {
+            if let Err(why) = fs::create_dir_all(out_dir) {
+                panic!("couldn't create {}: {}", out_dir.display(), why);
+            }
+        }
+        let comment = match marker {
+            Marker::Comment(comment) => format!("{} generated by {}\n", comment, tool),
+            Marker::NoComment => {
+                record_generated(path);
+                "".to_string()
+            }
+        };
+        let mut file = match File::create(path) {
+            Err(why) => panic!("couldn't create {}: {}", path.display(), why),
+            Ok(file) => file,
+        };
+        write!(file, "{}{}", comment, contents).expect("couldn't write to file");
+    }
+}
+
+// the file doesn't exist, is empty, starts with a "generated by" comment or is recorded in the .generated file
+pub fn is_generated(path: &Path, marker: Marker) -> bool {
+    if !path.exists() {
+        return true;
+    }
+    let contents = match fs::read_to_string(path) {
+        Err(_) => return false,
+        Ok(contents) => contents,
+    };
+    if contents.trim().is_empty() {
+        return true;
+    }
+    match marker {
+        Marker::Comment(comment) => {
+            let first_line = contents.lines().next().unwrap_or("").trim();
+            return first_line.starts_with(comment) && first_line.contains("generated by");
+        }
+        Marker::NoComment => return is_recorded(path),
+    }
+}
+
+fn is_recorded(path: &Path) -> bool {
+    let list = fs::read_to_string(generated_list_path(path)).unwrap_or_default();
+    return list.lines().any(|line| Some(line) == file_name(path).as_deref());
+}
+
+fn record_generated(path: &Path) {
+    if is_recorded(path) {
+        return;
+    }
+    let list_path = generated_list_path(path);
+    let name = match file_name(path) {
+        Some(name) => name,
+        None => panic!("error: invalid path {}", path.display()),
+    };
+    let mut file = match OpenOptions::new().create(true).append(true).open(&list_path) {
+        Err(why) => panic!("couldn't open {}: {}", list_path.display(), why),
+        Ok(file) => file,
+    };
+    writeln!(file, "{}", name).expect("couldn't write to file");
+}
+
+fn generated_list_path(path: &Path) -> PathBuf {
+    return path.parent().unwrap_or(Path::new("")).join(GENERATED_LIST);
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    return path.file_name().map(|name| name.to_string_lossy().to_string());
+}
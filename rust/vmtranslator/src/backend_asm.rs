@@ -0,0 +1,256 @@
+// vm commands to hack assembly lang
+// コンピュータシステムの理論と実装 §7,8
+
+use std::collections::HashSet;
+use crate::diagnostic::{Diagnostic, SourceLine};
+use crate::vm_command::{Command, Operator, Segment, VmFile};
+
+// options of code generation
+pub struct Options {
+    pub debug_info: bool,
+    pub checked: bool,
+}
+
+// stack: RAM[256..2047], heap: RAM[2048..16383]
+const STACK_END: usize = 2048;
+
+// error codes written into RAM[15] by the trap routine
+const TRAP_PUSH_OVERFLOW: usize = 1;
+const TRAP_CALL_OVERFLOW: usize = 2;
+const TRAP_LOCALS_OVERFLOW: usize = 3;
+
+pub fn vmfiles_to_asm(vm_files: &Vec<VmFile>, live_functions: &Option<HashSet<String>>, options: &Options, diagnostics: &mut Vec<Diagnostic>) -> String {
+    let mut eq_gt_lt_count = 0;
+    let mut return_address_count = 0;
+
+    // boot strap code: set SP,LCL,ARG and call Sys.init
+    let mut asm_string = "// boot strap code\n@256\nD=A\n@SP\nM=D\n@ARG\nM=D\n@5\nD=A\n@SP\nDM=D+M\n@LCL\nM=D\n@Sys.init\n0;JMP\n".to_string();
+    if options.checked {
+        asm_string += &trap_to_asm();
+    }
+    
+    // converting .vm files to asm code
+    for vm_file in vm_files {
+        asm_string += &vm_to_asm(vm_file, live_functions, options, &mut eq_gt_lt_count, &mut return_address_count, diagnostics);
+    }
+    return asm_string;
+}
+
+// all errors in the file are reported into diagnostics
+fn vm_to_asm(vm_file: &VmFile, live_functions: &Option<HashSet<String>>, options: &Options, eq_gt_lt_count: &mut usize, return_address_count: &mut usize, diagnostics: &mut Vec<Diagnostic>) -> String {
+    let file_stem = vm_file.path.file_stem().expect("error: invalid filename").to_str().expect("error: invalid filename");     // static variables are named as Foo.x (not Foo.vm.x)
+    let mut asm_string = "".to_string();
+    let mut unreachable_function = false;
+    let mut current_function = "";
+    for line in &vm_file.lines {
+        // skip functions which are never called
+        if let Command::Function(name, _) = &line.command {
+            current_function = name;
+            if let Some(live) = live_functions {
+                unreachable_function = !live.contains(name);
+            }
+        }
+        if unreachable_function {
+            continue;
+        }
+
+        // debug info: [// @vm Foo.vm line] before the asm code of each vm command (line is 1-based)
+        if options.debug_info {
+            asm_string += &format!("// @vm {} {}\n", vm_file.filename, line.row_num + 1);
+        }
+
+        let source = SourceLine {file: &vm_file.file, row_num: line.row_num, text: &line.text};
+        let code = match &line.command {
+            Command::Push(segment, index) => {
+                let guard = if options.checked {stack_guard_to_asm(1, TRAP_PUSH_OVERFLOW)} else {"".to_string()};
+                push_to_asm(*segment, *index, &source, file_stem).map(|code| guard + &code)
+            }
+            Command::Pop(segment, index) => pop_to_asm(*segment, *index, &source, file_stem),
+            Command::Arithmetic(operator) => Ok(arithmetic_to_asm(*operator, eq_gt_lt_count)),
+            Command::Label(label) => Ok(format!("({}) // label\n", scoped_label(label, current_function))),
+            Command::Goto(label) => Ok(format!("@{} // goto\n0;JMP\n", scoped_label(label, current_function))),
+            Command::IfGoto(label) => Ok(format!("// if-goto\n@SP\nAM=M-1\nD=M\n@{}\nD;JNE\n", scoped_label(label, current_function))),
+            Command::Function(name, vars_num) => Ok(function_to_asm(name, *vars_num, options.checked)),
+            Command::Call(name, args_num) => Ok(call_to_asm(name, *args_num, return_address_count, options.checked)),
+            Command::Return => Ok(return_to_asm()),
+        };
+        match code {
+            Ok(code) => asm_string += &code,
+            Err(diagnostic) => diagnostics.push(diagnostic),
+        }
+    }
+    return asm_string;
+}
+
+// subroutines
+// out of range indexes are reported by vmlint too
+fn push_to_asm(segment: Segment, index: usize, source: &SourceLine, file_stem: &str) -> Result<String, Diagnostic> {
+    let push_d = "@SP\nA=M\nM=D\n@SP\nM=M+1\n";
+    match segment {
+        Segment::Local | Segment::Argument | Segment::This | Segment::That => {
+            return Ok(format!("// push {} {}\n@{}\nD=M\n@{}\nA=D+A\nD=M\n{}", segment.name(), index, base_symbol(segment), index, push_d));
+        }
+        Segment::Pointer => {
+            let symbol = pointer_symbol(index, source)?;
+            return Ok(format!("// push pointer {}\n@{}\nD=M\n{}", index, symbol, push_d));
+        }
+        Segment::Temp => {
+            check_temp_index(index, source)?;
+            return Ok(format!("// push temp {}\n@{}\nD=A\n@5\nA=D+A\nD=M\n{}", index, index, push_d));
+        }
+        Segment::Constant => {
+            return Ok(format!("// push constant {}\n@{}\nD=A\n{}", index, index, push_d));
+        }
+        Segment::Static => {
+            return Ok(format!("// push static {}\n@{}.{}\nD=M\n{}", index, file_stem, index, push_d));
+        }
+    }
+}
+
+fn pop_to_asm(segment: Segment, index: usize, source: &SourceLine, file_stem: &str) -> Result<String, Diagnostic> {
+    // the address is computed into R13 before the value is popped
+    let pop_to_r13 = "@R13\nM=D\n@SP\nAM=M-1\nD=M\n@R13\nA=M\nM=D\n";
+    match segment {
+        Segment::Local | Segment::Argument | Segment::This | Segment::That => {
+            return Ok(format!("// pop {} {}\n@{}\nD=M\n@{}\nD=D+A\n{}", segment.name(), index, base_symbol(segment), index, pop_to_r13));
+        }
+        Segment::Pointer => {
+            let symbol = pointer_symbol(index, source)?;
+            return Ok(format!("// pop pointer {}\n@SP\nAM=M-1\nD=M\n@{}\nM=D\n", index, symbol));
+        }
+        Segment::Temp => {
+            check_temp_index(index, source)?;
+            return Ok(format!("// pop temp {}\n@{}\nD=A\n@5\nD=D+A\n{}", index, index, pop_to_r13));
+        }
+        Segment::Constant => {
+            return Err(source.error(1, "'pop constant' is not defined".to_string())
+                .with_hint("`constant` is a virtual segment: use 'pop temp 0' to discard a value"));
+        }
+        Segment::Static => {
+            return Ok(format!("// pop static {}\n@SP\nAM=M-1\nD=M\n@{}.{}\nM=D\n", index, file_stem, index));
+        }
+    }
+}
+
+// local, argument, this and that are based on the pointers
+fn base_symbol(segment: Segment) -> &'static str {
+    match segment {
+        Segment::Local => return "LCL",
+        Segment::Argument => return "ARG",
+        Segment::This => return "THIS",
+        Segment::That => return "THAT",
+        _ => unreachable!("{} is not based on a pointer", segment.name()),
+    }
+}
+
+fn pointer_symbol(index: usize, source: &SourceLine) -> Result<&'static str, Diagnostic> {
+    if index == 0 {
+        return Ok("THIS");
+    } else if index == 1 {
+        return Ok("THAT");
+    } else {
+        return Err(source.error(2, "pointer index is out of range".to_string())
+            .with_hint("`pointer` only accepts 0 (THIS) or 1 (THAT)"));
+    }
+}
+
+fn check_temp_index(index: usize, source: &SourceLine) -> Result<(), Diagnostic> {
+    if index >= 8 {
+        return Err(source.error(2, "temp index is out of range".to_string())
+            .with_hint("`temp` only accepts 0..7"));
+    }
+    return Ok(());
+}
+
+fn arithmetic_to_asm(operator: Operator, eq_gt_lt_count: &mut usize) -> String {
+    // binary operators: y -> R13, then x (the top of the stack) = x op y
+    let binary = |name: &str, operation: &str| format!("// {}\n@SP\nAM=M-1\nD=M\n@R13\nM=D\n@SP\nA=M-1\nD=M\n@R13\n{}\n@SP\nA=M-1\nM=D\n", name, operation);
+    match operator {
+        Operator::Add => return binary("add", "D=D+M"),
+        Operator::Sub => return binary("sub", "D=D-M"),
+        Operator::And => return binary("and", "D=D&M"),
+        Operator::Or => return binary("or", "D=D|M"),
+        Operator::Neg => return "// neg\n@SP\nA=M-1\nM=-M\n".to_string(),
+        Operator::Not => return "// not\n@SP\nA=M-1\nM=!M\n".to_string(),
+        Operator::Eq | Operator::Gt | Operator::Lt => return eq_gt_lt_to_asm(operator.name(), eq_gt_lt_count),
+    }
+}
+
+fn eq_gt_lt_to_asm(code: &str, count: &mut usize) -> String {
+    let upper_eq_gt_lt = code.to_uppercase();           // EQ or GT or LT
+    let mut asm_string = format!("// {}\n", code);      // initial comment: [// eq] or [// gt] or [// lt]
+    asm_string += "@SP\nAM=M-1\nD=M\n@R13\nM=D\n@SP\nA=M-1\nD=M\n@R13\nD=D-M\n";                                                    // pop + pop
+    asm_string = format!("{}@TRUECASE{}\nD;J{}\n@SP\nA=M-1\nM=0\n@RESULT{}\n0;JMP\n", asm_string, count, upper_eq_gt_lt, count);    // FALSE case
+    asm_string = format!("{}(TRUECASE{})\n@SP\nA=M-1\nM=-1\n(RESULT{})\n", asm_string, count, count);                               // TRUE case
+    *count += 1;
+    return asm_string;
+}
+
+// labels are scoped by function: LABEL in Foo.bar -> Foo.bar$LABEL
+fn scoped_label(label: &str, current_function: &str) -> String {
+    if current_function.is_empty() {
+        return label.to_string();
+    } else {
+        return format!("{}${}", current_function, label);
+    }
+}
+
+fn call_to_asm(func_name: &str, args_num: usize, return_address_count: &mut usize, checked: bool) -> String {
+    let mut asm_func_call = format!("// call {}\n", func_name);
+    if checked {
+        asm_func_call += &stack_guard_to_asm(5, TRAP_CALL_OVERFLOW);                                            // room for the frame
+    }
+    asm_func_call += &format!("@ReturnAddress{}\nD=A\n@SP\nA=M\nM=D\n@SP\nM=M+1\n", return_address_count);      // push return_address
+    asm_func_call += "@LCL\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n";                                                  // push LCL
+    asm_func_call += "@ARG\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n";                                                  // push ARG
+    asm_func_call += "@THIS\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n";                                                 // push THIS
+    asm_func_call += "@THAT\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n";                                                 // push THAT
+    asm_func_call += "@SP\nD=M\n@LCL\nM=D\n";                                                                   // LCL = SP
+    asm_func_call += &format!("@SP\nD=M\n@5\nD=D-A\n@{}\nD=D-A\n@ARG\nM=D\n", args_num);                        // ARG = SP - 5 - args_num
+    asm_func_call += &format!("@{}\n0;JMP\n(ReturnAddress{})\n", func_name, return_address_count);              // goto func + (return_address)
+    *return_address_count += 1;
+    return asm_func_call;
+}
+
+fn function_to_asm(func_name: &str, vars_num: usize, checked: bool) -> String {
+    let mut asm_def_func = format!("// function {}\n({})\n", func_name, func_name);     // (function)
+    if checked && vars_num > 0 {
+        asm_def_func += &stack_guard_to_asm(vars_num, TRAP_LOCALS_OVERFLOW);              // room for local variables
+    }
+    for _i in 0..vars_num {
+        asm_def_func += "@SP\nA=M\nM=0\n@SP\nM=M+1\n";                                  // push 0 * vars_num: initializing LCL and set SP
+    }
+    return asm_def_func;
+}
+
+// jump to the trap routine unless SP + words <= STACK_END
+fn stack_guard_to_asm(words: usize, error_code: usize) -> String {
+    if words > STACK_END {
+        return format!("@VM_TRAP_{}\n0;JMP\n", error_code);
+    }
+    return format!("@SP\nD=M\n@{}\nD=D-A\n@VM_TRAP_{}\nD;JGT\n", STACK_END - words, error_code);
+}
+
+// trap routine: RAM[15] = error code, then halt
+fn trap_to_asm() -> String {
+    let mut asm_string = "// trap routine for --checked\n".to_string();
+    for error_code in [TRAP_PUSH_OVERFLOW, TRAP_CALL_OVERFLOW, TRAP_LOCALS_OVERFLOW] {
+        asm_string += &format!("(VM_TRAP_{})\n@{}\nD=A\n@VM_TRAP\n0;JMP\n", error_code, error_code);
+    }
+    asm_string += "(VM_TRAP)\n@R15\nM=D\n(VM_HALT)\n@VM_HALT\n0;JMP\n";
+    return asm_string;
+}
+
+fn return_to_asm() -> String {
+    let mut asm_string = "// return\n".to_string();
+    asm_string += "@SP\nAM=M-1\nD=M\n@R14\nM=D\n";                  // return_value: pop -> R14
+    asm_string += "@ARG\nD=M\n@SP\nM=D+1\n";                        // SP_new = ARG 1
+    asm_string += "@LCL\nD=M\n@R13\nAM=D-1\nD=M\n@THAT\nM=D\n";     // THAT_new = LCL - 1
+    asm_string += "@R13\nAM=M-1\nD=M\n@THIS\nM=D\n";                // THIS_new = LCL - 2
+    asm_string += "@R13\nAM=M-1\nD=M\n@ARG\nM=D\n";                 // ARG_new = LCL - 3
+    asm_string += "@R13\nAM=M-1\nD=M\n@LCL\nM=D\n";                 // LCL_new = LCL - 4
+    asm_string += "@R13\nA=M-1\nD=M\n@R13\nM=D\n";                  // return_address = LCL - 5: -> R13
+    asm_string += "@R14\nD=M\n@SP\nA=M-1\nM=D\n";                   // R14 -> *(SP_new - 1)
+    asm_string += "@R13\nA=M\n0;JMP\n";                             // return_address -> jump
+    return asm_string;
+}
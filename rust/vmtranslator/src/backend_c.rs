@@ -0,0 +1,261 @@
+// converting VM to portable C: an alternative backend for validating compiler output on the host
+// the Hack RAM is simulated (int16_t with 16-bit wraparound), vm functions become C functions
+
+use std::collections::{HashMap, HashSet};
+use crate::diagnostic::{Diagnostic, SourceLine};
+use crate::vm_command::{Command, Operator, Segment, VmFile, VmLine};
+
+const PRELUDE: &str = r#"/* generated by vmtranslator --backend c */
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static int16_t RAM[32768];
+#define M(address) RAM[(uint16_t)(address) & 0x7fff]
+#define SP RAM[0]
+#define LCL RAM[1]
+#define ARG RAM[2]
+#define THIS RAM[3]
+#define THAT RAM[4]
+
+/* 16-bit wraparound */
+#define WRAP(x) ((int16_t)(uint16_t)(x))
+
+static int vm_argc;
+static char **vm_argv;
+
+static inline void push(int16_t value) { M(SP) = value; SP = WRAP(SP + 1); }
+static inline int16_t pop(void) { SP = WRAP(SP - 1); return M(SP); }
+
+static inline void vm_add(void) { int16_t y = pop(); int16_t x = pop(); push(WRAP(x + y)); }
+static inline void vm_sub(void) { int16_t y = pop(); int16_t x = pop(); push(WRAP(x - y)); }
+static inline void vm_neg(void) { push(WRAP(-pop())); }
+static inline void vm_and(void) { int16_t y = pop(); int16_t x = pop(); push(x & y); }
+static inline void vm_or(void) { int16_t y = pop(); int16_t x = pop(); push(x | y); }
+static inline void vm_not(void) { push(~pop()); }
+/* compared via x - y like the Hack code of the asm backend */
+static inline void vm_eq(void) { int16_t y = pop(); int16_t x = pop(); push(WRAP(x - y) == 0 ? -1 : 0); }
+static inline void vm_gt(void) { int16_t y = pop(); int16_t x = pop(); push(WRAP(x - y) > 0 ? -1 : 0); }
+static inline void vm_lt(void) { int16_t y = pop(); int16_t x = pop(); push(WRAP(x - y) < 0 ? -1 : 0); }
+
+/* the frame keeps the Hack layout, but the return address is the native call stack */
+static inline void vm_call(void (*function)(void), int16_t args_num) {
+    push(0);
+    push(LCL);
+    push(ARG);
+    push(THIS);
+    push(THAT);
+    ARG = WRAP(SP - 5 - args_num);
+    LCL = SP;
+    function();
+}
+
+static inline void vm_return(void) {
+    int16_t frame = LCL;
+    M(ARG) = pop();
+    SP = WRAP(ARG + 1);
+    THAT = M(frame - 1);
+    THIS = M(frame - 2);
+    ARG = M(frame - 3);
+    LCL = M(frame - 4);
+}
+
+/* halt: prints "address value" of RAM addresses given in the command line */
+static void vm_halt(void) {
+    for (int i = 1; i < vm_argc; i++) {
+        long address = strtol(vm_argv[i], NULL, 10);
+        printf("%ld %d\n", address, M(address));
+    }
+    exit(0);
+}
+"#;
+
+// an error is reported into diagnostics when the program has no entry point
+pub fn vmfiles_to_c(vm_files: &Vec<VmFile>, live_functions: &Option<HashSet<String>>, diagnostics: &mut Vec<Diagnostic>) -> String {
+    // static variables are allocated from RAM[16] in order of appearance like the assembler
+    let mut statics: HashMap<(String, usize), usize> = HashMap::new();
+    let mut declarations = "".to_string();
+    let mut definitions = "".to_string();
+    let mut defined = HashSet::new();
+
+    for vm_file in vm_files {
+        let file_stem = vm_file.path.file_stem().expect("error: invalid filename").to_str().expect("error: invalid filename");
+        let lines = &vm_file.lines;
+        let mut current_function: Option<String> = None;
+        let mut skip = true;        // code outside of any function is never executed
+        let mut targets = HashSet::new();
+        for (i, line) in lines.iter().enumerate() {
+            if let Command::Function(name, vars_num) = &line.command {
+                if current_function.is_some() && !skip {
+                    definitions += "}\n\n";
+                }
+                skip = match live_functions {
+                    Some(live) => !live.contains(name),
+                    None => false,
+                };
+                current_function = Some(name.to_string());
+                if skip {
+                    continue;
+                }
+                targets = goto_targets(&lines[i + 1..]);
+                defined.insert(name.to_string());
+                declarations += &format!("static void {}(void);\n", function_symbol(name));
+                definitions += &format!("/* function {} {} */\nstatic void {}(void) {{\n", name, vars_num, function_symbol(name));
+                if name == "Sys.halt" {
+                    // never returns: the native halt prints the result instead of looping forever
+                    definitions += "    vm_halt();\n";
+                }
+                for _i in 0..*vars_num {
+                    definitions += "    push(0);\n";
+                }
+                continue;
+            }
+            if skip {
+                continue;
+            }
+            if let Command::Label(label) = &line.command {
+                // unused labels are omitted for a warning-free compilation
+                if !targets.contains(label) {
+                    continue;
+                }
+            }
+            definitions += &command_to_c(&line.command, file_stem, &mut statics, lines.get(i + 1).map(|next| &next.command));
+        }
+        if current_function.is_some() && !skip {
+            definitions += "}\n\n";
+        }
+    }
+    let entry = if defined.contains("Sys.init") {"Sys.init"} else {"Main.main"};
+    if !defined.contains(entry) {
+        let file = vm_files.first().map_or("", |vm_file| vm_file.file.as_str());
+        diagnostics.push(SourceLine {file, row_num: 0, text: ""}.error(0, "neither Sys.init nor Main.main is defined".to_string())
+            .with_hint("the program starts from Sys.init, or from Main.main when the Jack OS is linked by the host toolchain"));
+        return "".to_string();
+    }
+
+    let mut c_string = PRELUDE.to_string();
+    c_string += "\n";
+    c_string += &declarations;
+    c_string += "\n";
+    c_string += &definitions;
+    // boot strap code: same as the asm backend, then Sys.init (or Main.main and halt)
+    c_string += "int main(int argc, char **argv) {\n    vm_argc = argc;\n    vm_argv = argv;\n";
+    c_string += "    SP = 256;\n    ARG = 256;\n    SP = 261;\n    LCL = 261;\n";
+    if entry == "Sys.init" {
+        c_string += &format!("    {}();\n", function_symbol("Sys.init"));
+    } else {
+        c_string += &format!("    vm_call({}, 0);\n", function_symbol("Main.main"));
+    }
+    c_string += "    vm_halt();\n    return 0;\n}\n";
+    return c_string;
+}
+
+// labels used by goto/if-goto in the function which begins with lines
+fn goto_targets(lines: &[VmLine]) -> HashSet<String> {
+    let mut targets = HashSet::new();
+    for line in lines {
+        match &line.command {
+            Command::Function(_, _) => break,
+            Command::Goto(label) | Command::IfGoto(label) => {
+                targets.insert(label.to_string());
+            }
+            _ => {}
+        }
+    }
+    return targets;
+}
+
+fn command_to_c(command: &Command, file_stem: &str, statics: &mut HashMap<(String, usize), usize>, next: Option<&Command>) -> String {
+    match command {
+        Command::Push(segment, index) => {
+            let value = match segment {
+                Segment::Constant => format!("{}", index),
+                _ => segment_to_c(*segment, *index, file_stem, statics),
+            };
+            return format!("    push({});\n", value);
+        }
+        Command::Pop(segment, index) => {
+            return format!("    {} = pop();\n", segment_to_c(*segment, *index, file_stem, statics));
+        }
+        Command::Arithmetic(operator) => {
+            let function = match operator {
+                Operator::Add => "vm_add",
+                Operator::Sub => "vm_sub",
+                Operator::Neg => "vm_neg",
+                Operator::Eq => "vm_eq",
+                Operator::Gt => "vm_gt",
+                Operator::Lt => "vm_lt",
+                Operator::And => "vm_and",
+                Operator::Or => "vm_or",
+                Operator::Not => "vm_not",
+            };
+            return format!("    {}();\n", function);
+        }
+        Command::Label(label) => {
+            // [label X] + [goto X] is an infinite loop which does nothing: halt
+            if let Some(Command::Goto(target)) = next {
+                if target == label {
+                    return format!("{}:\n    vm_halt();\n", label_symbol(label));
+                }
+            }
+            return format!("{}: ;\n", label_symbol(label));
+        }
+        Command::Goto(label) => {
+            return format!("    goto {};\n", label_symbol(label));
+        }
+        Command::IfGoto(label) => {
+            return format!("    if (pop() != 0) goto {};\n", label_symbol(label));
+        }
+        Command::Call(name, args_num) => {
+            return format!("    vm_call({}, {});\n", function_symbol(name), args_num);
+        }
+        Command::Return => {
+            return "    vm_return();\n    return;\n".to_string();
+        }
+        Command::Function(_, _) => panic!("program error: invalid function call: in command_to_c"),
+    }
+}
+
+fn segment_to_c(segment: Segment, index: usize, file_stem: &str, statics: &mut HashMap<(String, usize), usize>) -> String {
+    match segment {
+        Segment::Local => format!("M(LCL + {})", index),
+        Segment::Argument => format!("M(ARG + {})", index),
+        Segment::This => format!("M(THIS + {})", index),
+        Segment::That => format!("M(THAT + {})", index),
+        Segment::Pointer => format!("RAM[{}]", 3 + index),
+        Segment::Temp => format!("RAM[{}]", 5 + index),
+        Segment::Static => {
+            let next_address = 16 + statics.len();
+            let address = statics.entry((file_stem.to_string(), index)).or_insert(next_address);
+            format!("RAM[{}] /* {}.{} */", address, file_stem, index)
+        }
+        Segment::Constant => panic!("program error: invalid function call: in segment_to_c"),
+    }
+}
+
+// C identifiers: [A-Za-z0-9] as is, '_' -> "_0", '.' -> "_1", '$' -> "_2", others -> "_x{code}_"
+fn mangle(name: &str) -> String {
+    let mut mangled = "".to_string();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            mangled.push(c);
+        } else if c == '_' {
+            mangled += "_0";
+        } else if c == '.' {
+            mangled += "_1";
+        } else if c == '$' {
+            mangled += "_2";
+        } else {
+            mangled += &format!("_x{:x}_", c as u32);
+        }
+    }
+    return mangled;
+}
+
+fn function_symbol(name: &str) -> String {
+    return format!("f_{}", mangle(name));
+}
+
+fn label_symbol(label: &str) -> String {
+    return format!("l_{}", mangle(label));
+}
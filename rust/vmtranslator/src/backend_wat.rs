@@ -0,0 +1,262 @@
+// converting VM to WebAssembly text format (WAT): running Jack programs in browsers
+// the Hack RAM is a linear memory (2 bytes per word), the segment pointers are globals
+// functions of the Jack OS which are not given as .vm files are imported from the host: "os" "Math.multiply"
+// without Sys.vm the host initializes its OS, and main calls Main.main directly
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use crate::diagnostic::{Diagnostic, SourceLine};
+use crate::vm_command::{Command, Operator, Segment, VmFile, VmLine};
+
+pub const OS_CLASSES: [&str; 8] = ["Array", "Keyboard", "Math", "Memory", "Output", "Screen", "String", "Sys"];
+
+const PRELUDE: &str = r#"  ;; the Hack RAM: 32K words
+  (memory (export "memory") 1)
+  (global $SP (mut i32) (i32.const 0))
+  (global $LCL (mut i32) (i32.const 0))
+  (global $ARG (mut i32) (i32.const 0))
+  (global $THIS (mut i32) (i32.const 0))
+  (global $THAT (mut i32) (i32.const 0))
+
+  (func $peek (param $address i32) (result i32)
+    (i32.load16_s (i32.shl (i32.and (local.get $address) (i32.const 32767)) (i32.const 1))))
+  (func $poke (param $address i32) (param $value i32)
+    (i32.store16 (i32.shl (i32.and (local.get $address) (i32.const 32767)) (i32.const 1)) (local.get $value)))
+  (func $push (param $value i32)
+    (call $poke (global.get $SP) (local.get $value))
+    (global.set $SP (i32.add (global.get $SP) (i32.const 1))))
+  (func $pop (result i32)
+    (global.set $SP (i32.sub (global.get $SP) (i32.const 1)))
+    (call $peek (global.get $SP)))
+
+  ;; 16-bit wraparound: values are kept sign extended
+  (func $vm_add (local $y i32) (local.set $y (call $pop)) (call $push (i32.extend16_s (i32.add (call $pop) (local.get $y)))))
+  (func $vm_sub (local $y i32) (local.set $y (call $pop)) (call $push (i32.extend16_s (i32.sub (call $pop) (local.get $y)))))
+  (func $vm_neg (call $push (i32.extend16_s (i32.sub (i32.const 0) (call $pop)))))
+  (func $vm_and (call $push (i32.and (call $pop) (call $pop))))
+  (func $vm_or (call $push (i32.or (call $pop) (call $pop))))
+  (func $vm_not (call $push (i32.xor (call $pop) (i32.const -1))))
+  ;; compared via x - y like the Hack code of the asm backend
+  (func $vm_diff (result i32) (local $y i32) (local.set $y (call $pop)) (i32.extend16_s (i32.sub (call $pop) (local.get $y))))
+  (func $vm_eq (call $push (i32.sub (i32.const 0) (i32.eqz (call $vm_diff)))))
+  (func $vm_gt (call $push (i32.sub (i32.const 0) (i32.gt_s (call $vm_diff) (i32.const 0)))))
+  (func $vm_lt (call $push (i32.sub (i32.const 0) (i32.lt_s (call $vm_diff) (i32.const 0)))))
+
+  ;; the frame keeps the Hack layout, but the return address is the wasm call stack
+  (func $vm_frame (param $args_num i32)
+    (call $push (i32.const 0))
+    (call $push (global.get $LCL))
+    (call $push (global.get $ARG))
+    (call $push (global.get $THIS))
+    (call $push (global.get $THAT))
+    (global.set $ARG (i32.sub (global.get $SP) (i32.add (local.get $args_num) (i32.const 5))))
+    (global.set $LCL (global.get $SP)))
+  (func $vm_return (local $frame i32)
+    (local.set $frame (global.get $LCL))
+    (call $poke (global.get $ARG) (call $pop))
+    (global.set $SP (i32.add (global.get $ARG) (i32.const 1)))
+    (global.set $THAT (call $peek (i32.sub (local.get $frame) (i32.const 1))))
+    (global.set $THIS (call $peek (i32.sub (local.get $frame) (i32.const 2))))
+    (global.set $ARG (call $peek (i32.sub (local.get $frame) (i32.const 3))))
+    (global.set $LCL (call $peek (i32.sub (local.get $frame) (i32.const 4)))))
+"#;
+
+// an error is reported into diagnostics when the program has no entry point
+pub fn vmfiles_to_wat(vm_files: &Vec<VmFile>, live_functions: &Option<HashSet<String>>, diagnostics: &mut Vec<Diagnostic>) -> String {
+    let mut defined = HashSet::new();
+    for vm_file in vm_files {
+        for line in &vm_file.lines {
+            if let Command::Function(name, _) = &line.command {
+                if is_live(name, live_functions) {
+                    defined.insert(name.to_string());
+                }
+            }
+        }
+    }
+    let entry = if defined.contains("Sys.init") {"Sys.init"} else {"Main.main"};
+    if !defined.contains(entry) {
+        let file = vm_files.first().map_or("", |vm_file| vm_file.file.as_str());
+        diagnostics.push(SourceLine {file, row_num: 0, text: ""}.error(0, "neither Sys.init nor Main.main is defined".to_string())
+            .with_hint("the program starts from Sys.init, or from Main.main when the Jack OS is imported from the host"));
+        return "".to_string();
+    }
+
+    // static variables are allocated from RAM[16] in order of appearance like the assembler
+    let mut statics: HashMap<(String, usize), usize> = HashMap::new();
+    // called functions which are not defined: function_name -> args_num
+    let mut imports: BTreeMap<String, usize> = BTreeMap::new();
+    let mut functions = "".to_string();
+    for vm_file in vm_files {
+        let file_stem = vm_file.path.file_stem().expect("error: invalid filename").to_str().expect("error: invalid filename");
+        let lines = &vm_file.lines;
+        for (i, line) in lines.iter().enumerate() {
+            // code outside of any function is never executed
+            if let Command::Function(name, _) = &line.command {
+                if is_live(name, live_functions) {
+                    let end = match lines[i + 1..].iter().position(|line| matches!(line.command, Command::Function(_, _))) {
+                        Some(n) => i + 1 + n,
+                        None => lines.len(),
+                    };
+                    functions += &function_to_wat(&lines[i..end], file_stem, &defined, &mut statics, &mut imports);
+                }
+            }
+        }
+    }
+
+    let mut wat_string = "(module\n".to_string();
+    wat_string += "  ;; generated by vmtranslator --backend wat\n";
+    wat_string += "  (import \"vm\" \"halt\" (func $vm_halt))\n";
+    for (name, args_num) in &imports {
+        wat_string += &format!("  (import \"os\" \"{}\" (func ${} (param{}) (result i32)))\n", name, name, " i32".repeat(*args_num));
+    }
+    wat_string += "\n";
+    wat_string += PRELUDE;
+    wat_string += "\n";
+    wat_string += &functions;
+    // boot strap code: same as the asm backend, then Sys.init (or Main.main and halt)
+    wat_string += "  (func (export \"main\")\n";
+    wat_string += "    (global.set $SP (i32.const 256))\n    (global.set $ARG (i32.const 256))\n";
+    wat_string += "    (global.set $SP (i32.const 261))\n    (global.set $LCL (i32.const 261))\n";
+    if entry == "Sys.init" {
+        wat_string += "    (call $Sys.init))\n)\n";
+    } else {
+        wat_string += "    (call $vm_frame (i32.const 0)) (call $Main.main)\n    (call $vm_halt))\n)\n";
+    }
+    return wat_string;
+}
+
+fn is_live(function_name: &str, live_functions: &Option<HashSet<String>>) -> bool {
+    return match live_functions {
+        Some(live) => live.contains(function_name),
+        None => true,
+    };
+}
+
+// lines: [function f k] and its body
+// wasm has no goto: the body is divided into blocks at labels, and goto sets $pc and branches to the dispatcher
+//   (loop $dispatch (block $s2 (block $s1 (block $s0 (br_table $s0 $s1 $s2 (local.get $pc))) s0 code) s1 code) s2 code)
+fn function_to_wat(lines: &[VmLine], file_stem: &str, defined: &HashSet<String>, statics: &mut HashMap<(String, usize), usize>, imports: &mut BTreeMap<String, usize>) -> String {
+    let (name, vars_num) = match &lines[0].command {
+        Command::Function(name, vars_num) => (name, *vars_num),
+        _ => panic!("program error: invalid function call: in function_to_wat"),
+    };
+    // label -> index of the block which begins with it (0: the entry)
+    let mut blocks: HashMap<String, usize> = HashMap::new();
+    for line in &lines[1..] {
+        if let Command::Label(label) = &line.command {
+            let index = blocks.len() + 1;
+            blocks.entry(label.to_string()).or_insert(index);
+        }
+    }
+
+    let mut wat_string = format!("  ;; function {} {}\n  (func ${}\n", name, vars_num, name);
+    if !blocks.is_empty() {
+        wat_string += "    (local $pc i32)\n";
+    }
+    if name == "Sys.halt" {
+        // never returns: the host stops the program instead of looping forever
+        wat_string += "    (call $vm_halt)\n    (unreachable)\n";
+    }
+    for _i in 0..vars_num {
+        wat_string += "    (call $push (i32.const 0))\n";
+    }
+    if !blocks.is_empty() {
+        wat_string += "    (loop $dispatch\n";
+        for index in (0..=blocks.len()).rev() {
+            wat_string += &format!("    (block $s{}\n", index);
+        }
+        let targets: Vec<String> = (0..=blocks.len()).map(|index| format!("$s{}", index)).collect();
+        wat_string += &format!("    (br_table {} (local.get $pc)))\n", targets.join(" "));
+    }
+
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        let code = match &line.command {
+            Command::Push(segment, index) => {
+                let value = match segment {
+                    Segment::Constant => format!("(i32.const {})", index),
+                    Segment::Pointer => if *index == 0 {"(global.get $THIS)".to_string()} else {"(global.get $THAT)".to_string()},
+                    _ => format!("(call $peek {})", segment_address(*segment, *index, file_stem, statics)),
+                };
+                format!("(call $push {})", value)
+            }
+            Command::Pop(segment, index) => match segment {
+                Segment::Pointer => if *index == 0 {"(global.set $THIS (call $pop))".to_string()} else {"(global.set $THAT (call $pop))".to_string()},
+                _ => format!("(call $poke {} (call $pop))", segment_address(*segment, *index, file_stem, statics)),
+            },
+            Command::Arithmetic(operator) => {
+                let function = match operator {
+                    Operator::Add => "$vm_add",
+                    Operator::Sub => "$vm_sub",
+                    Operator::Neg => "$vm_neg",
+                    Operator::Eq => "$vm_eq",
+                    Operator::Gt => "$vm_gt",
+                    Operator::Lt => "$vm_lt",
+                    Operator::And => "$vm_and",
+                    Operator::Or => "$vm_or",
+                    Operator::Not => "$vm_not",
+                };
+                format!("(call {})", function)
+            }
+            Command::Label(label) => {
+                // the end of the previous block
+                let mut code = format!("    )\n    ;; label {}", label);
+                // [label X] + [goto X] is an infinite loop which does nothing: halt
+                if let Some(Command::Goto(target)) = lines.get(i + 1).map(|next| &next.command) {
+                    if target == label {
+                        code += "\n    (call $vm_halt)\n    (unreachable)";
+                    }
+                }
+                code
+            }
+            Command::Goto(label) => {
+                format!("(local.set $pc (i32.const {})) (br $dispatch)", blocks[label])
+            }
+            Command::IfGoto(label) => {
+                format!("(if (call $pop) (then (local.set $pc (i32.const {})) (br $dispatch)))", blocks[label])
+            }
+            Command::Call(callee, args_num) => {
+                if defined.contains(callee) {
+                    format!("(call $vm_frame (i32.const {})) (call ${})", args_num, callee)
+                } else {
+                    // imported: the arguments are passed as parameters, and the result is pushed
+                    imports.entry(callee.to_string()).or_insert(*args_num);
+                    let mut code = "".to_string();
+                    for n in 0..*args_num {
+                        code += &format!("(call $peek (i32.sub (global.get $SP) (i32.const {}))) ", args_num - n);
+                    }
+                    code += &format!("(call ${})\n    (global.set $SP (i32.sub (global.get $SP) (i32.const {})))\n    (call $push)", callee, args_num);
+                    code
+                }
+            }
+            Command::Return => "(call $vm_return) (return)".to_string(),
+            Command::Function(_, _) => panic!("program error: invalid function call: in function_to_wat"),
+        };
+        if let Command::Label(_) = &line.command {
+            wat_string += &code;
+            wat_string += "\n";
+        } else {
+            wat_string += &format!("    {}\n", code);
+        }
+    }
+    if !blocks.is_empty() {
+        wat_string += "    )\n";
+    }
+    wat_string += "  )\n\n";
+    return wat_string;
+}
+
+// the address of segment[index] as a wasm expression
+fn segment_address(segment: Segment, index: usize, file_stem: &str, statics: &mut HashMap<(String, usize), usize>) -> String {
+    match segment {
+        Segment::Local => format!("(i32.add (global.get $LCL) (i32.const {}))", index),
+        Segment::Argument => format!("(i32.add (global.get $ARG) (i32.const {}))", index),
+        Segment::This => format!("(i32.add (global.get $THIS) (i32.const {}))", index),
+        Segment::That => format!("(i32.add (global.get $THAT) (i32.const {}))", index),
+        Segment::Temp => format!("(i32.const {})", 5 + index),
+        Segment::Static => {
+            let next_address = 16 + statics.len();
+            let address = statics.entry((file_stem.to_string(), index)).or_insert(next_address);
+            format!("(i32.const {}) (; {}.{} ;)", address, file_stem, index)
+        }
+        Segment::Pointer | Segment::Constant => panic!("program error: invalid function call: in segment_address"),
+    }
+}
@@ -0,0 +1,78 @@
+// call graph of a whole vm program
+// functions unreachable from Sys.init are dropped before code generation
+
+use std::collections::{BTreeMap, HashSet};
+use crate::vm_command::{Command, VmFile};
+
+pub struct CallGraph {
+    // function_name -> called functions (in order of first call)
+    edges: BTreeMap<String, Vec<String>>,
+}
+
+impl CallGraph {
+    pub fn build(vm_files: &Vec<VmFile>) -> Self {
+        let mut edges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for vm_file in vm_files {
+            let mut current_function: Option<String> = None;
+            for line in &vm_file.lines {
+                match &line.command {
+                    Command::Function(name, _) => {
+                        edges.entry(name.to_string()).or_insert(vec![]);
+                        current_function = Some(name.to_string());
+                    }
+                    Command::Call(name, _) => {
+                        // calls outside of any function are never executed
+                        if let Some(caller) = &current_function {
+                            let callees = edges.get_mut(caller).unwrap();
+                            if !callees.contains(name) {
+                                callees.push(name.to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        return CallGraph {edges};
+    }
+
+    pub fn contains(&self, function_name: &str) -> bool {
+        return self.edges.contains_key(function_name);
+    }
+
+    // defined functions reachable from root (depth first search)
+    pub fn reachable_from(&self, root: &str) -> HashSet<String> {
+        let mut reachable = HashSet::new();
+        let mut stack = vec![root.to_string()];
+        while let Some(function_name) = stack.pop() {
+            if let Some(callees) = self.edges.get(&function_name) {
+                if reachable.insert(function_name.to_string()) {
+                    for callee in callees {
+                        stack.push(callee.to_string());
+                    }
+                }
+            }
+        }
+        return reachable;
+    }
+
+    // Graphviz DOT format: unreachable functions are drawn with dashed gray lines
+    pub fn to_dot(&self, reachable: &HashSet<String>) -> String {
+        let mut dot = "digraph call_graph {\n    node [shape=box];\n".to_string();
+        for (caller, callees) in &self.edges {
+            if reachable.contains(caller) {
+                dot += &format!("    \"{}\";\n", caller);
+            } else {
+                dot += &format!("    \"{}\" [style=dashed, color=gray];\n", caller);
+            }
+            for callee in callees {
+                if !self.edges.contains_key(callee) {
+                    dot += &format!("    \"{}\" [color=red];\n", callee);         // called but not defined
+                }
+                dot += &format!("    \"{}\" -> \"{}\";\n", caller, callee);
+            }
+        }
+        dot += "}\n";
+        return dot;
+    }
+}
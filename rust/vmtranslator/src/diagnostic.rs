@@ -0,0 +1,130 @@
+// diagnostics of vm code: errors and warnings with 1-based line/column, the offending token and a hint
+// printed for human or as JSON for editor integration
+
+#[derive(PartialEq)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+pub struct Diagnostic {
+    pub level: Level,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn with_hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        return self;
+    }
+
+    pub fn is_error(&self) -> bool {
+        return self.level == Level::Error;
+    }
+
+    pub fn print(&self) {
+        let level = if self.is_error() {"error"} else {"warning"};
+        eprintln!("{}: {}: '{}' in {} line {} column {}", level, self.message, self.token, self.file, self.line, self.column);
+        if let Some(hint) = &self.hint {
+            eprintln!("    hint: {}", hint);
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let level = if self.is_error() {"error"} else {"warning"};
+        let hint = match &self.hint {
+            Some(hint) => json_string(hint),
+            None => "null".to_string(),
+        };
+        return format!("{{\"level\": \"{}\", \"file\": {}, \"line\": {}, \"column\": {}, \"token\": {}, \"message\": {}, \"hint\": {}}}",
+            level, json_string(&self.file), self.line, self.column, json_string(&self.token), json_string(&self.message), hint);
+    }
+}
+
+// prints all diagnostics as a JSON array into stdout
+pub fn print_json(diagnostics: &[Diagnostic]) {
+    let mut json = "[".to_string();
+    for (i, diagnostic) in diagnostics.iter().enumerate() {
+        if i != 0 {
+            json += ",";
+        }
+        json += "\n  ";
+        json += &diagnostic.to_json();
+    }
+    json += "\n]";
+    println!("{}", json);
+}
+
+fn json_string(string: &str) -> String {
+    let mut json = "\"".to_string();
+    for c in string.chars() {
+        match c {
+            '"' => json += "\\\"",
+            '\\' => json += "\\\\",
+            '\n' => json += "\\n",
+            '\t' => json += "\\t",
+            c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+            c => json.push(c),
+        }
+    }
+    json += "\"";
+    return json;
+}
+
+// a line of vm code: locates the n-th word of the line for diagnostics
+pub struct SourceLine<'a> {
+    pub file: &'a str,
+    pub row_num: usize,
+    pub text: &'a str,
+}
+
+impl<'a> SourceLine<'a> {
+    pub fn error(&self, word_index: usize, message: String) -> Diagnostic {
+        return self.diagnostic(Level::Error, word_index, message);
+    }
+
+    pub fn warning(&self, word_index: usize, message: String) -> Diagnostic {
+        return self.diagnostic(Level::Warning, word_index, message);
+    }
+
+    fn diagnostic(&self, level: Level, word_index: usize, message: String) -> Diagnostic {
+        // column of the word (1-based): the end of the line when the word is missing
+        let mut column = self.text.chars().count() + 1;
+        let mut token = "".to_string();
+        let mut in_word = false;
+        let mut words_num = 0;
+        for (i, c) in self.text.chars().enumerate() {
+            if c.is_whitespace() {
+                if in_word && words_num == word_index + 1 {
+                    break;
+                }
+                in_word = false;
+            } else {
+                if !in_word {
+                    in_word = true;
+                    words_num += 1;
+                    if words_num == word_index + 1 {
+                        column = i + 1;
+                    }
+                }
+                if words_num == word_index + 1 {
+                    token.push(c);
+                }
+            }
+        }
+        return Diagnostic {
+            level,
+            file: self.file.to_string(),
+            line: self.row_num + 1,
+            column,
+            token,
+            message,
+            hint: None,
+        };
+    }
+}
@@ -0,0 +1,154 @@
+// converting VM to hack assembly lang: shared by vmtranslator and the jack build driver
+// コンピュータシステムの理論と実装 §7,8
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub mod vm_command;
+use crate::vm_command::{Command, VmFile};
+
+pub mod diagnostic;
+use crate::diagnostic::Diagnostic;
+
+pub mod vmlint;
+pub mod call_graph;
+pub mod backend_asm;
+pub mod backend_c;
+pub mod backend_wat;
+
+// foo.vm itself, or .vm files in the directory and its subdirectories (sorted by path)
+pub fn vmfile_paths(path: &Path) -> Vec<PathBuf> {
+    if !path.is_dir() {
+        if path.extension().is_some_and(|extension| extension == "vm") {
+            return vec![path.to_path_buf()];
+        }
+        panic!("error: invalid path {}: input path/to/dir or path/to/foo.vm", path.display());
+    }
+
+    // open directory 
+    let directory = match path.read_dir() {
+        Err(why) => panic!("couldn't open directory {}: {}", path.display(), why),
+        Ok(dir) => dir,
+    };
+    let mut paths = vec![];
+    for dir_entry in directory {
+        if let Ok(entry) = dir_entry {
+            let path_of_entry = entry.path();
+            if path_of_entry.is_dir() {
+                paths.append(&mut vmfile_paths(&path_of_entry));
+            } else if let Some(extension) = path_of_entry.extension() {
+                if extension == "vm" {
+                    paths.push(path_of_entry);
+                }
+            }
+        } else {
+            eprintln!("warning: couldn't access to some entry in {}", path.display());
+            continue;
+        }
+    }
+    paths.sort();
+    return paths;
+}
+
+// Foo -> path/to/libdir/Foo.vm: the first library directory has priority
+fn library_paths(lib_dirs: &Vec<PathBuf>) -> HashMap<String, PathBuf> {
+    let mut library = HashMap::new();
+    for lib_dir in lib_dirs {
+        if !lib_dir.is_dir() {
+            panic!("error: library directory {} is not found", lib_dir.display());
+        }
+        for path in vmfile_paths(lib_dir) {
+            let file_stem = path.file_stem().expect("error: invalid filename").to_str().expect("error: invalid filename").to_string();
+            library.entry(file_stem).or_insert(path);
+        }
+    }
+    return library;
+}
+
+// parses and checks .vm files in input paths (and library modules)
+pub fn vmlint(input_paths: &Vec<PathBuf>, lib_dirs: &Vec<PathBuf>, host_classes: &[&str]) -> (Vec<VmFile>, Vec<Diagnostic>) {
+    let mut vm_files = vec![];
+    let mut diagnostics = vec![];
+    let mut loaded = HashSet::new();
+    let mut paths = vec![];
+    for input_path in input_paths {
+        for path in vmfile_paths(input_path) {
+            if loaded.insert(path.clone()) {
+                paths.push(path);
+            }
+        }
+    }
+
+    // linking library modules: Foo.vm is loaded from libdir when Foo.bar is called but not defined
+    let library = library_paths(lib_dirs);
+    while !paths.is_empty() {
+        for path in &paths {
+            let (vm_file, mut parse_errors) = VmFile::parse(path);
+            diagnostics.append(&mut parse_errors);
+            vm_files.push(vm_file);
+        }
+        paths = vec![];
+        for class_name in undefined_classes(&vm_files) {
+            if let Some(path) = library.get(&class_name) {
+                if loaded.insert(path.clone()) {
+                    paths.push(path.clone());
+                }
+            }
+        }
+    }
+    // lines with syntax errors are missing from the commands: skip the analysis to avoid misleading messages
+    if diagnostics.is_empty() {
+        diagnostics = vmlint::lint(&vm_files, host_classes);
+    }
+    return (vm_files, diagnostics);
+}
+
+// prints diagnostics for human (stderr) or as JSON (stdout): returns false if some error is found
+pub fn report(diagnostics: &Vec<Diagnostic>, json: bool) -> bool {
+    let errors_num = diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+    if json {
+        diagnostic::print_json(diagnostics);
+    } else {
+        for diagnostic in diagnostics {
+            diagnostic.print();
+        }
+        if errors_num != 0 {
+            eprintln!("error: aborting due to {} previous error(s)", errors_num);
+        }
+    }
+    return errors_num == 0;
+}
+
+// Foo of called Foo.bar which is not defined in vm_files
+// Sys.init is called by the boot strap code: Sys.vm is linked from the library too
+fn undefined_classes(vm_files: &Vec<VmFile>) -> Vec<String> {
+    let mut defined = HashSet::new();
+    for vm_file in vm_files {
+        for line in &vm_file.lines {
+            if let Command::Function(name, _) = &line.command {
+                defined.insert(name.to_string());
+            }
+        }
+    }
+    let mut class_names = vec![];
+    if !defined.contains("Sys.init") {
+        class_names.push("Sys".to_string());
+    }
+    for vm_file in vm_files {
+        for line in &vm_file.lines {
+            if let Command::Call(name, _) = &line.command {
+                if !defined.contains(name) {
+                    if let Some((class_name, _)) = name.split_once('.') {
+                        if !class_names.contains(&class_name.to_string()) {
+                            class_names.push(class_name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    return class_names;
+}
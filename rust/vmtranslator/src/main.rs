@@ -0,0 +1,134 @@
+// converting VM to hack assembly lang
+// コンピュータシステムの理論と実装 §7,8
+
+// explicit returns are the style of the tools
+#![allow(clippy::needless_return)]
+
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+use vmtranslator::backend_asm::{vmfiles_to_asm, Options};
+use vmtranslator::backend_c;
+use vmtranslator::backend_wat;
+use vmtranslator::call_graph::CallGraph;
+use tool_output::{Marker, OutDir};
+use vmtranslator::{report, vmlint};
+
+fn main() {
+    // get paths and options from command line: path is a directory (searched recursively) or foo.vm
+    //   -L libdir: searches Foo.vm in libdir for calls to undefined Foo.bar (e.g. Jack OS)
+    //   --lint: checks .vm files without translation
+    //   --keep-unreachable: translates functions which are never called from Sys.init
+    //   --dot: writes the call graph into dir.dot
+    //   --debug-info: annotates asm code with the original .vm file and line
+    //   --checked: traps stack overflow into the heap (error code -> RAM[15], then halt)
+    //   --json: prints errors and warnings as JSON into stdout
+    //   --backend c: writes a C program simulating the Hack RAM into dir.c instead of dir.asm
+    //   --backend wat: writes a WebAssembly text module into dir.wat (Jack OS classes are imported from the host)
+    //   -o dir, --out-dir dir: writes dir.asm (and the others) into dir instead of next to the input (-o -: into stdout)
+    let args: Vec<String> = env::args().collect();
+    let mut lint_only = false;
+    let mut keep_unreachable = false;
+    let mut dot_output = false;
+    let mut json = false;
+    let mut backend = "asm".to_string();
+    let mut out_dir = OutDir::NextToInput;
+    let mut options = Options {debug_info: false, checked: false};
+    let mut input_paths = vec![];
+    let mut lib_dirs = vec![];
+    let mut args_iter = args.iter().skip(1);
+    while let Some(arg) = args_iter.next() {
+        if arg == "-L" {
+            match args_iter.next() {
+                Some(lib_dir) => lib_dirs.push(PathBuf::from(lib_dir)),
+                None => panic!("error: -L needs a library directory"),
+            }
+        } else if let Some(lib_dir) = arg.strip_prefix("-L") {
+            lib_dirs.push(PathBuf::from(lib_dir));
+        } else if arg == "-o" || arg == "--out-dir" {
+            match args_iter.next() {
+                Some(dir) => out_dir = OutDir::from_arg(dir),
+                None => panic!("error: {} needs an output directory (or - for stdout)", arg),
+            }
+        } else if arg == "--lint" {
+            lint_only = true;
+        } else if arg == "--keep-unreachable" {
+            keep_unreachable = true;
+        } else if arg == "--dot" {
+            dot_output = true;
+        } else if arg == "--debug-info" {
+            options.debug_info = true;
+        } else if arg == "--checked" {
+            options.checked = true;
+        } else if arg == "--json" {
+            json = true;
+        } else if arg == "--backend" {
+            match args_iter.next() {
+                Some(name) if name == "asm" || name == "c" || name == "wat" => backend = name.to_string(),
+                _ => panic!("error: --backend needs asm, c or wat"),
+            }
+        } else {
+            input_paths.push(PathBuf::from(arg));
+        }
+    }
+    if input_paths.is_empty() {
+        panic!("input path: ./vmtranslator [--lint] [--keep-unreachable] [--dot] [--debug-info] [--checked] [--json] [--backend asm|c|wat] [-o out_dir|-] [-L libdir] path/to/dir...");
+    }
+    // output: dir.asm (or foo.asm) from the first path
+    let path = input_paths[0].clone();
+    if let OutDir::Stdout = out_dir {
+        if json || dot_output {
+            panic!("error: -o - writes the translated code into stdout: it can't be used with --json or --dot");
+        }
+    }
+
+    // static analysis before translation
+    let host_classes: &[&str] = if backend == "wat" {&backend_wat::OS_CLASSES} else {&[]};
+    let (vm_files, mut diagnostics) = vmlint(&input_paths, &lib_dirs, host_classes);
+    if lint_only || diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+        if !report(&diagnostics, json) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    // whole program analysis: functions unreachable from Sys.init are dropped
+    let call_graph = CallGraph::build(&vm_files);
+    let reachable = call_graph.reachable_from("Sys.init");
+    if dot_output {
+        out_dir.write(&out_dir.output_path(&path, "dot"), &call_graph.to_dot(&reachable), "vmtranslator", Marker::Comment("//"));
+    }
+    let live_functions = if keep_unreachable || !call_graph.contains("Sys.init") {
+        None
+    } else {
+        Some(reachable)
+    };
+
+    if backend == "c" {
+        // the same vm commands to portable C (compiled with a host toolchain)
+        let c_string = backend_c::vmfiles_to_c(&vm_files, &live_functions, &mut diagnostics);
+        if !report(&diagnostics, json) {
+            process::exit(1);
+        }
+        out_dir.write(&out_dir.output_path(&path, "c"), &c_string, "vmtranslator", Marker::Comment("//"));
+        return;
+    } else if backend == "wat" {
+        // WebAssembly text format for browsers: the host instantiates it with the Jack OS
+        let wat_string = backend_wat::vmfiles_to_wat(&vm_files, &live_functions, &mut diagnostics);
+        if !report(&diagnostics, json) {
+            process::exit(1);
+        }
+        out_dir.write(&out_dir.output_path(&path, "wat"), &wat_string, "vmtranslator", Marker::Comment(";;"));
+        return;
+    }
+
+    // convert foo.vm files in path folder to assembly lang 
+    let asm_string = vmfiles_to_asm(&vm_files, &live_functions, &options, &mut diagnostics);
+    if !report(&diagnostics, json) {
+        process::exit(1);
+    }
+
+    // write assembly lang into file
+    out_dir.write(&out_dir.output_path(&path, "asm"), &format!("{}\n", asm_string), "vmtranslator", Marker::Comment("//"));
+}
@@ -0,0 +1,245 @@
+// vm command package
+// parsing vm code into commands
+// コンピュータシステムの理論と実装 §7,8
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use crate::diagnostic::{Diagnostic, SourceLine};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Segment {
+    Local,
+    Argument,
+    This,
+    That,
+    Pointer,
+    Temp,
+    Constant,
+    Static,
+}
+
+impl Segment {
+    fn from_str(word: &str) -> Option<Segment> {
+        match word {
+            "local" => Some(Segment::Local),
+            "argument" => Some(Segment::Argument),
+            "this" => Some(Segment::This),
+            "that" => Some(Segment::That),
+            "pointer" => Some(Segment::Pointer),
+            "temp" => Some(Segment::Temp),
+            "constant" => Some(Segment::Constant),
+            "static" => Some(Segment::Static),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Segment::Local => "local",
+            Segment::Argument => "argument",
+            Segment::This => "this",
+            Segment::That => "that",
+            Segment::Pointer => "pointer",
+            Segment::Temp => "temp",
+            Segment::Constant => "constant",
+            Segment::Static => "static",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Neg,
+    Eq,
+    Gt,
+    Lt,
+    And,
+    Or,
+    Not,
+}
+
+impl Operator {
+    fn from_str(word: &str) -> Option<Operator> {
+        match word {
+            "add" => Some(Operator::Add),
+            "sub" => Some(Operator::Sub),
+            "neg" => Some(Operator::Neg),
+            "eq" => Some(Operator::Eq),
+            "gt" => Some(Operator::Gt),
+            "lt" => Some(Operator::Lt),
+            "and" => Some(Operator::And),
+            "or" => Some(Operator::Or),
+            "not" => Some(Operator::Not),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operator::Add => "add",
+            Operator::Sub => "sub",
+            Operator::Neg => "neg",
+            Operator::Eq => "eq",
+            Operator::Gt => "gt",
+            Operator::Lt => "lt",
+            Operator::And => "and",
+            Operator::Or => "or",
+            Operator::Not => "not",
+        }
+    }
+
+    // neg / not take one value, the others take two
+    pub fn is_unary(&self) -> bool {
+        return *self == Operator::Neg || *self == Operator::Not;
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Command {
+    Push(Segment, usize),
+    Pop(Segment, usize),
+    Arithmetic(Operator),
+    Label(String),
+    Goto(String),
+    IfGoto(String),
+    Function(String, usize),
+    Call(String, usize),
+    Return,
+}
+
+pub struct VmLine {
+    pub command: Command,
+    pub row_num: usize,
+    pub text: String,
+}
+
+pub struct VmFile {
+    pub path: PathBuf,
+    pub file: String,           // path for diagnostics
+    pub filename: String,
+    pub lines: Vec<VmLine>,
+}
+
+impl VmFile {
+    // parse foo.vm: returns the commands and diagnostics of lines which couldn't be parsed
+    pub fn parse(path: &PathBuf) -> (VmFile, Vec<Diagnostic>) {
+        let filename = path.file_name().expect("error: invalid filename").to_str().expect("error: invalid filename");
+        let vmfile = match File::open(path) {
+            Err(why) => panic!("couldn't open {}: {}", path.display(), why),
+            Ok(file) => file,
+        };
+
+        let file = path.display().to_string();
+        let mut lines = vec![];
+        let mut errors = vec![];
+        for (row_num, line) in BufReader::new(vmfile).lines().enumerate() {
+            let unwraped_line = match line {
+                Err(why) => panic!("error: {}: couldn't read line {} in {}", why, row_num + 1, filename),
+                Ok(content) => content,
+            };
+            let source = SourceLine {file: &file, row_num, text: &unwraped_line};
+            match parse_line(&source) {
+                Ok(Some(command)) => lines.push(VmLine {command, row_num, text: unwraped_line.to_string()}),
+                Ok(None) => {}
+                Err(diagnostic) => errors.push(diagnostic),
+            }
+        }
+
+        let vm_file = VmFile {
+            path: path.clone(),
+            file: file.to_string(),
+            filename: filename.to_string(),
+            lines,
+        };
+        return (vm_file, errors);
+    }
+}
+
+// returns None for blank lines and comments
+fn parse_line(source: &SourceLine) -> Result<Option<Command>, Diagnostic> {
+    // strip comments: "push constant 1 // comment"
+    let code = match source.text.find("//") {
+        Some(position) => &source.text[..position],
+        None => source.text,
+    };
+    let words: Vec<&str> = code.split_whitespace().collect();
+    if words.is_empty() {
+        return Ok(None);
+    }
+
+    let expected_words = match words[0] {
+        "push" | "pop" | "function" | "call" => 3,
+        "label" | "goto" | "if-goto" => 2,
+        _ => 1,
+    };
+    if words.len() < expected_words {
+        let usage = match words[0] {
+            "push" | "pop" => "push/pop segment index",
+            "function" => "function name vars_num",
+            "call" => "call name args_num",
+            _ => "label/goto/if-goto LABEL",
+        };
+        return Err(source.error(words.len(), format!("'{}' needs {} argument(s), but {} given", words[0], expected_words - 1, words.len() - 1))
+            .with_hint(usage));
+    } else if words.len() > expected_words {
+        return Err(source.error(expected_words, format!("unexpected word after '{}' command", words[0]))
+            .with_hint("comments begin with '//'"));
+    }
+
+    let command = match words[0] {
+        "push" | "pop" => {
+            let segment = match Segment::from_str(words[1]) {
+                Some(segment) => segment,
+                None => return Err(source.error(1, "unknown segment".to_string())
+                    .with_hint("segments are local, argument, this, that, pointer, temp, constant and static")),
+            };
+            let index = match words[2].parse::<usize>() {
+                Ok(int) => int,
+                Err(_) => return Err(source.error(2, "invalid index".to_string())
+                    .with_hint("an index is a non-negative integer")),
+            };
+            if words[0] == "push" {
+                Command::Push(segment, index)
+            } else {
+                Command::Pop(segment, index)
+            }
+        }
+        "label" | "goto" | "if-goto" => {
+            let label = words[1].to_string();
+            if label.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(source.error(1, "invalid label".to_string())
+                    .with_hint("a label can't begin with a digit"));
+            }
+            if words[0] == "label" {
+                Command::Label(label)
+            } else if words[0] == "goto" {
+                Command::Goto(label)
+            } else {
+                Command::IfGoto(label)
+            }
+        }
+        "function" | "call" => {
+            let name = words[1].to_string();
+            let number = match words[2].parse::<usize>() {
+                Ok(int) => int,
+                Err(_) => return Err(source.error(2, format!("invalid number of {}", if words[0] == "function" {"local variables"} else {"arguments"}))
+                    .with_hint("the number is a non-negative integer")),
+            };
+            if words[0] == "function" {
+                Command::Function(name, number)
+            } else {
+                Command::Call(name, number)
+            }
+        }
+        "return" => Command::Return,
+        word => match Operator::from_str(word) {
+            Some(operator) => Command::Arithmetic(operator),
+            None => return Err(source.error(0, "unknown command".to_string())
+                .with_hint("commands are push, pop, add, sub, neg, eq, gt, lt, and, or, not, label, goto, if-goto, function, call and return")),
+        },
+    };
+    return Ok(Some(command));
+}
@@ -0,0 +1,226 @@
+// static analysis of vm code before translation
+// stack depth, segment indices, control flow and calls across the directory
+
+use std::collections::HashMap;
+use crate::diagnostic::{Diagnostic, SourceLine};
+use crate::vm_command::{Command, Segment, VmFile};
+
+// function_name -> (path, row_num) of its definition, and every call site
+struct CallInfo {
+    definitions: HashMap<String, (String, usize)>,
+    max_argument: HashMap<String, usize>,
+    call_sites: Vec<(String, usize, usize, usize)>,       // (function_name, args_num, index of vm_files, index of lines)
+}
+
+fn source(vm_file: &VmFile, i: usize) -> SourceLine<'_> {
+    return SourceLine {file: &vm_file.file, row_num: vm_file.lines[i].row_num, text: &vm_file.lines[i].text};
+}
+
+// host_classes: functions of the classes are provided by the host (e.g. the Jack OS of the wat backend)
+pub fn lint(vm_files: &[VmFile], host_classes: &[&str]) -> Vec<Diagnostic> {
+    let mut messages = vec![];
+    let mut call_info = CallInfo {
+        definitions: HashMap::new(),
+        max_argument: HashMap::new(),
+        call_sites: vec![],
+    };
+
+    for (file_index, vm_file) in vm_files.iter().enumerate() {
+        // dividing the file into functions: [start, end)
+        let mut starts = vec![];
+        for (i, line) in vm_file.lines.iter().enumerate() {
+            if let Command::Function(_, _) = line.command {
+                starts.push(i);
+            }
+        }
+        let first = if starts.is_empty() {vm_file.lines.len()} else {starts[0]};
+        if first > 0 {
+            messages.push(source(vm_file, 0).warning(0, "code outside of any function is never executed".to_string())
+                .with_hint("the boot strap code calls Sys.init"));
+        }
+        for (n, start) in starts.iter().enumerate() {
+            let end = if n + 1 < starts.len() {starts[n + 1]} else {vm_file.lines.len()};
+            lint_function(vm_file, file_index, *start, end, &mut call_info, &mut messages);
+        }
+    }
+
+    // calls across the directory
+    let mut arities: HashMap<String, (usize, String, usize)> = HashMap::new();
+    for (function_name, args_num, file_index, i) in &call_info.call_sites {
+        let vm_file = &vm_files[*file_index];
+        let row_num = vm_file.lines[*i].row_num;
+        let class_name = function_name.split('.').next().unwrap();
+        if !call_info.definitions.contains_key(function_name) && !host_classes.contains(&class_name) {
+            messages.push(source(vm_file, *i).error(1, "call to undefined function".to_string())
+                .with_hint("add the .vm file to the inputs, or its directory with -L libdir"));
+            continue;
+        }
+        if let Some((first_num, first_filename, first_row_num)) = arities.get(function_name) {
+            if first_num != args_num {
+                messages.push(source(vm_file, *i).error(2, format!("'call {} {}' doesn't match 'call {} {}' in {} line {}",
+                    function_name, args_num, function_name, first_num, first_filename, first_row_num + 1)));
+            }
+        } else {
+            arities.insert(function_name.to_string(), (*args_num, vm_file.filename.to_string(), row_num));
+        }
+        if let Some(max_argument) = call_info.max_argument.get(function_name) {
+            if *max_argument >= *args_num {
+                messages.push(source(vm_file, *i).error(2, format!("'{}' reads argument {}, but is called with {} argument(s)",
+                    function_name, max_argument, args_num)));
+            }
+        }
+    }
+    return messages;
+}
+
+fn lint_function(vm_file: &VmFile, file_index: usize, start: usize, end: usize, call_info: &mut CallInfo, messages: &mut Vec<Diagnostic>) {
+    let lines = &vm_file.lines;
+    let function_name = match &lines[start].command {
+        Command::Function(name, _) => name.to_string(),
+        _ => panic!("program error: invalid function call: in lint_function"),
+    };
+    let path = vm_file.path.display().to_string();
+    if let Some((first_path, first_row_num)) = call_info.definitions.get(&function_name) {
+        messages.push(source(vm_file, start).error(1, format!("function '{}' is already defined in {} line {}",
+            function_name, first_path, first_row_num + 1)));
+    } else {
+        call_info.definitions.insert(function_name.to_string(), (path, lines[start].row_num));
+    }
+
+    // labels are scoped by function
+    let mut labels: HashMap<String, usize> = HashMap::new();
+    for i in start..end {
+        if let Command::Label(label) = &lines[i].command {
+            if let Some(first) = labels.get(label) {
+                messages.push(source(vm_file, i).error(1, format!("label '{}' is already defined in function '{}' line {}",
+                    label, function_name, lines[*first].row_num + 1)));
+            } else {
+                labels.insert(label.to_string(), i);
+            }
+        }
+    }
+
+    // segments, indices and jump targets
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        match &line.command {
+            Command::Pop(Segment::Constant, _) => {
+                messages.push(source(vm_file, i).error(1, "'pop constant' is not defined".to_string())
+                    .with_hint("`constant` is a virtual segment: use 'pop temp 0' to discard a value"));
+            }
+            Command::Push(Segment::Constant, index) if *index > 32767 => {
+                messages.push(source(vm_file, i).error(2, "constant is out of range".to_string())
+                    .with_hint("`constant` only accepts 0..32767: use 'neg' or 'not' for other values"));
+            }
+            Command::Push(Segment::Temp, index) | Command::Pop(Segment::Temp, index) if *index > 7 => {
+                messages.push(source(vm_file, i).error(2, "temp index is out of range".to_string())
+                    .with_hint("`temp` only accepts 0..7"));
+            }
+            Command::Push(Segment::Pointer, index) | Command::Pop(Segment::Pointer, index) if *index > 1 => {
+                messages.push(source(vm_file, i).error(2, "pointer index is out of range".to_string())
+                    .with_hint("`pointer` only accepts 0 (THIS) or 1 (THAT)"));
+            }
+            Command::Push(Segment::Argument, index) | Command::Pop(Segment::Argument, index) => {
+                let max_argument = call_info.max_argument.entry(function_name.to_string()).or_insert(*index);
+                if *max_argument < *index {
+                    *max_argument = *index;
+                }
+            }
+            Command::Goto(label) | Command::IfGoto(label) if !labels.contains_key(label) => {
+                messages.push(source(vm_file, i).error(1, format!("label is not defined in function '{}'", function_name))
+                    .with_hint("labels are scoped by function"));
+            }
+            Command::Call(name, args_num) => {
+                call_info.call_sites.push((name.to_string(), *args_num, file_index, i));
+            }
+            _ => {}
+        }
+    }
+
+    // stack depth of each command via data flow analysis from the top of the function
+    let mut depths: Vec<Option<usize>> = vec![None; end - start];
+    let mut mismatch_reported = vec![false; end - start];
+    let mut falls_off = false;
+    depths[0] = Some(0);
+    let mut worklist = vec![start];
+    while let Some(i) = worklist.pop() {
+        let depth = depths[i - start].unwrap();
+
+        // (values needed, values left)
+        let (needed, pushed) = match &lines[i].command {
+            Command::Push(_, _) => (0, 1),
+            Command::Pop(_, _) => (1, 0),
+            Command::Arithmetic(operator) => if operator.is_unary() {(1, 1)} else {(2, 1)},
+            Command::IfGoto(_) => (1, 0),
+            Command::Call(_, args_num) => (*args_num, 1),
+            Command::Return => (1, 0),
+            _ => (0, 0),
+        };
+        if depth < needed {
+            messages.push(source(vm_file, i).error(0, format!("stack underflow: '{}' needs {} value(s), but the stack has {}",
+                command_name(&lines[i].command), needed, depth)));
+        }
+        let new_depth = depth.saturating_sub(needed) + pushed;
+        if let Command::Return = lines[i].command {
+            if depth > 1 {
+                messages.push(source(vm_file, i).warning(0, format!("'return' leaves {} extra value(s) on the stack", depth - 1)));
+            }
+        }
+
+        let mut successors = vec![];
+        match &lines[i].command {
+            Command::Goto(label) => {
+                if let Some(target) = labels.get(label) {
+                    successors.push(*target);
+                }
+            }
+            Command::IfGoto(label) => {
+                successors.push(i + 1);
+                if let Some(target) = labels.get(label) {
+                    successors.push(*target);
+                }
+            }
+            Command::Return => {}
+            _ => successors.push(i + 1),
+        }
+
+        for successor in successors {
+            if successor == end {
+                if !falls_off {
+                    messages.push(source(vm_file, i).error(0, format!("function '{}' falls off the end without 'return'", function_name))
+                        .with_hint("the code would run into the next function"));
+                    falls_off = true;
+                }
+            } else if let Some(old_depth) = depths[successor - start] {
+                if old_depth != new_depth && !mismatch_reported[successor - start] {
+                    messages.push(source(vm_file, successor).error(0, format!("stack depth mismatch at '{}': {} value(s) on one path, {} on another",
+                        command_name(&lines[successor].command), old_depth, new_depth)));
+                    mismatch_reported[successor - start] = true;
+                }
+            } else {
+                depths[successor - start] = Some(new_depth);
+                worklist.push(successor);
+            }
+        }
+    }
+
+    // unreachable code: report the first command of each unreachable run
+    for i in start..end {
+        if depths[i - start].is_none() && depths[i - 1 - start].is_some() {
+            messages.push(source(vm_file, i).warning(0, "unreachable code".to_string()));
+        }
+    }
+}
+
+fn command_name(command: &Command) -> String {
+    match command {
+        Command::Push(segment, index) => format!("push {} {}", segment.name(), index),
+        Command::Pop(segment, index) => format!("pop {} {}", segment.name(), index),
+        Command::Arithmetic(operator) => operator.name().to_string(),
+        Command::Label(label) => format!("label {}", label),
+        Command::Goto(label) => format!("goto {}", label),
+        Command::IfGoto(label) => format!("if-goto {}", label),
+        Command::Function(name, vars_num) => format!("function {} {}", name, vars_num),
+        Command::Call(name, args_num) => format!("call {} {}", name, args_num),
+        Command::Return => "return".to_string(),
+    }
+}
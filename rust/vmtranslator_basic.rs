@@ -29,6 +29,7 @@ fn main() {
 
 fn vm_to_asm(path: &PathBuf) -> String {
     let filename = path.file_name().expect("error: invalid filename").to_str().expect("error: invalid filename");
+    let file_stem = path.file_stem().expect("error: invalid filename").to_str().expect("error: invalid filename");     // static variables are named as Foo.x (not Foo.vm.x)
     let vmfile = match File::open(&path) {
         Err(why) => panic!("couldn't open {}: {}", path.display(), why),
         Ok(file) => file,
@@ -50,7 +51,7 @@ fn vm_to_asm(path: &PathBuf) -> String {
             } else if line_vec[0].chars().nth(0).unwrap() == '/' && line_vec[0].chars().nth(1).unwrap() == '/' {
                 // skip comments: do nothing
             } else if line_vec[0] == "push" || line_vec[0] == "pop" {
-                asm_string += &push_or_pop_to_asm(line_vec, filename, row_num);
+                asm_string += &push_or_pop_to_asm(line_vec, filename, file_stem, row_num);
             } else if line_vec[0] == "add" {
                 asm_string += "// add\n@SP\nAM=M-1\nD=M\n@R13\nM=D\n@SP\nA=M-1\nD=M\n@R13\nD=D+M\n@SP\nA=M-1\nM=D\n";
             } else if line_vec[0] == "sub" {
@@ -83,7 +84,7 @@ fn vm_to_asm(path: &PathBuf) -> String {
 }
 
 // subroutines
-fn push_or_pop_to_asm(args: Vec<&str>, filename: &str, row_num: usize) -> String {
+fn push_or_pop_to_asm(args: Vec<&str>, filename: &str, file_stem: &str, row_num: usize) -> String {
     // error handling
     let words_num = args.len();
     if words_num < 3 {
@@ -173,9 +174,9 @@ fn push_or_pop_to_asm(args: Vec<&str>, filename: &str, row_num: usize) -> String
         }
     } else if args[1] == "static" {
         if args[0] == "push" {
-            return format!("// push static {}\n@{}.{}\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n", arg2_num, filename, arg2_num);
+            return format!("// push static {}\n@{}.{}\nD=M\n@SP\nA=M\nM=D\n@SP\nM=M+1\n", arg2_num, file_stem, arg2_num);
         } else if args[0] == "pop" {
-            return format!("// pop static {}\n@SP\nAM=M-1\nD=M\n@{}.{}\nM=D\n", arg2_num, filename, arg2_num);
+            return format!("// pop static {}\n@SP\nAM=M-1\nD=M\n@{}.{}\nM=D\n", arg2_num, file_stem, arg2_num);
         } else {
             panic!("syntax error: in {} line {}", filename, row_num);
         }